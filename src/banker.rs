@@ -6,16 +6,15 @@
 // April 7, 2023
 // Instructor: Dr. Michael Scherger
 //
-// NOTE: This program is written in Rust, and is compiled
-// using the Rust compiler. To run this program (assuming
+// NOTE: This program is written in Rust, and is built
+// with Cargo, as it is a thin client of the 'banker'
+// library in this crate. To run this program (assuming
 // Rust is installed), navigate to the directory containing
-// this file and run either of the following commands:
-// 1. 'cargo build', then 'cargo run -- <input file>'
-// OR
-// 2. 'rustc banker.rs', then './banker <input file>'
+// Cargo.toml and run 'cargo build', then
+// 'cargo run -- <input file>'.
 //
-// If using the first method, run 'cargo clean' to delete
-// the 'target' directory after executing.
+// Run 'cargo clean' to delete the 'target' directory
+// after executing.
 //
 //*********************************************************
 
@@ -25,686 +24,1268 @@
 use std::env;
 use std::process;
 use std::path::Path;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, IsTerminal, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use banker::display::{print_detection, print_matrix, print_parse_error, print_safe_sequence, print_state, print_trace, print_unsafe_report, print_vector, TableStyle};
+use banker::{detect_deadlock, Detection, Event, EventKind, EventOutcome, ParseError, RequestOutcome, Safety, TraceStep};
+use banker::generate::generate_state;
+use banker::repl::{run_repl, Repl};
+use banker::parse::{read_input, read_json_input, EventRow, LineReader, ParsedInput};
+use banker::sequences::MAX_COUNTED_PROCESSES;
+use banker::serialize::write_input;
+use banker::validate::{check_dimensions, check_event_values, check_state, CheckMode, InvariantViolation};
+use banker::json::{detection_to_json, input_to_json, names_to_json, outcome_to_json, policy_to_json, safety_to_json, state_to_json, trace_to_json, Json};
+use banker::{BankerState, SelectionPolicy};
+
+//name used for stdin in diagnostics
+const STDIN_NAME: &str = "<stdin>";
 
 //*********************************************************
 //
-// Print Vector Function
+// ExitStatus Enum
 //
-// Prints the type of vector and the vector itself, with
-// values labeled by the provided resource labels.
+// The exit code of the program, so that scripts can tell
+// how a run ended without reading its output. When several
+// events are denied, the first denial decides the code.
 //
-// Return Value
-// ------------
-// none
-//
-// Function Parameters
-// -------------------
-// vector      Vec<i32>   reference  vector to print
-// name        &str       reference  type of vector (e.g. "Request", "Available", etc.)
-// labels      Vec<char>  reference  resource labels for vector (e.g. ['A', 'B', 'C'])
-// process_id  usize      value      process ID for request vector (only used for request vectors, default 0)
-//
-// Local Variables
-// ---------------
-// i    usize   loop counter
+// Variants
+// --------
+// Granted         0   the state is safe and every event was granted
+// Usage           1   the command line arguments were not valid
+// Unsafe          2   the initial state is not safe
+// ExceedsClaim    3   a request exceeded the process's remaining claim
+// MustWait        4   a request had to wait for resources to become available
+// WouldBeUnsafe   5   granting a request would have left the system unsafe
+// InvalidInput    6   the input file, or an event in it, is not valid
+// IoError         7   the input file could not be read
+// Deadlocked      8   'detect' found processes that are deadlocked
 //
 //*********************************************************
-fn print_vector(vector: &Vec<i32>, name: &str, labels: &Vec<char>, process_id: usize) {
-
-    //print the header message specifying the vector type
-    eprintln!("The {} Vector is: ", name);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitStatus {
+    Granted = 0,
+    Usage = 1,
+    Unsafe = 2,
+    ExceedsClaim = 3,
+    MustWait = 4,
+    WouldBeUnsafe = 5,
+    InvalidInput = 6,
+    IoError = 7,
+    Deadlocked = 8,
+}
 
-    //handle formatting for request vector
-    if name == "Request" {
-        eprint!("  ");
+impl ExitStatus {
+
+    //the status for the outcome of an event; releases that are refused,
+    //malformed requests, and events for processes that do not exist,
+    //are invalid input
+    fn from_outcome(outcome: &EventOutcome) -> ExitStatus {
+        match outcome {
+            EventOutcome::Request(RequestOutcome::Granted) | EventOutcome::Release(Ok(())) => ExitStatus::Granted,
+            EventOutcome::Request(RequestOutcome::ExceedsClaim { .. }) => ExitStatus::ExceedsClaim,
+            EventOutcome::Request(RequestOutcome::MustWait { .. }) => ExitStatus::MustWait,
+            EventOutcome::Request(RequestOutcome::WouldBeUnsafe { .. }) => ExitStatus::WouldBeUnsafe,
+            EventOutcome::Request(RequestOutcome::UnknownProcess { .. } | RequestOutcome::WrongLength { .. }
+                                  | RequestOutcome::Negative { .. })
+            | EventOutcome::Release(Err(_)) => {
+                ExitStatus::InvalidInput
+            }
+        }
     }
 
-    //print the resource labels
-    for i in 0..labels.len() {
-
-        //handle shifting/formatting for values above 9
-        if vector[i] > 9 {
-            eprint!(" ");
+    //the status for an error found while reading the input file
+    fn from_parse_error(error: &ParseError) -> ExitStatus {
+        match error {
+            ParseError::Io { .. } => ExitStatus::IoError,
+            _ => ExitStatus::InvalidInput,
         }
-
-        eprint!("{} ", labels[i]);
     }
-    eprintln!("");
 
-    //handle formatting for request vector
-    if name == "Request" {
-        eprint!("{}:", process_id);
+    //ends the program with this status
+    fn exit(self) -> ! {
+        process::exit(self as i32)
     }
+}
 
-    //print the vector values
-    for i in 0..vector.len() {
-        eprint!("{} ", vector[i]);
+//the steps of the safety check a request would get, or None if it
+//is denied before the check (it exceeds the claim or must wait)
+fn request_trace(state: &BankerState, event: &Event, policy: &SelectionPolicy) -> Option<Vec<TraceStep>> {
+    let mut trial = state.clone();
+    match trial.fulfill_request(event.process_id, &event.vector) {
+        RequestOutcome::Granted => Some(trial.trace_safety_with(policy).expect("the policy was checked by 'load_scenario'").1),
+        _ => None,
     }
-    eprintln!("\n");
 }
 
 //*********************************************************
 //
-// Print Matrix Function
+// Banker's Algorithm Function
 //
-// Prints the type of matrix and the matrix itself, with
-// values labeled by the resource labels.
+// Runs the Banker's Algorithm on the state read from the
+// file using the banker library, applying each request and
+// release in order and carrying the state forward.
 //
 // Return Value
 // ------------
-// none
+// io::Result<ExitStatus>   how the run ended (the first denial, if any event
+//                          was denied), or the error encountered while writing
 //
 // Function Parameters
 // -------------------
-// matrix   Vec<Vec<i32>>  reference  matrix to print
-// name     &str           reference  type of matrix (e.g. "Max", "Allocation", etc.)
-// labels   Vec<char>      reference  resource labels for matrix (e.g. ['A', 'B', 'C'])
+// out               dyn Write        reference  where to print the results
+// state             BankerState      value      state of the system read from the file
+// events            Vec<Event>       reference  requests and releases to apply, in order
+// style             TableStyle       value      plain or bordered tables
+// trace             bool             value      whether to print the steps of each safety check
+// policy            SelectionPolicy  reference  how the safety algorithm picks the next process to finish
 //
 // Local Variables
 // ---------------
-// i       usize       loop counter used for both resource labels and rows in the matrix
-// j       usize       loop counter used for both rows and columns in the matrix
-// shift   Vec<bool>   used to indicate whether a matrix column needs to be shifted to the right (in case of values above 9)
+// resource_labels   Vec<String>     resource labels ("A", "B", "C", etc., or their names)
+// safe_sequence     Vec<usize>      safe sequence of the initial state
+// i                 usize           loop counter for events
+// event             Event           current request or release
+// process           String          label of the requesting/releasing process
+// name              String          name of the event ("REQUEST" or "RELEASE")
+// outcome           EventOutcome    whether the event was granted, and if not, why
+// status            ExitStatus      how the run has ended so far
 //
-//*********************************************************
-fn print_matrix(matrix: &Vec<Vec<i32>>, name: &str, labels: &Vec<char>) {
+//**********************************************************
+fn run_banker(out: &mut dyn Write, mut state: BankerState, events: &[Event], style: TableStyle, trace: bool,
+              policy: &SelectionPolicy) -> io::Result<ExitStatus> {
+    let resource_labels = state.names().resource_labels(state.num_resources());
 
-    //used to determine whether matrix columns need to be shifted to the right (in case of values above 9)
-    let mut shift: Vec<bool> = vec![false; labels.len()];
+    //print number of processes and resource types
+    writeln!(out, "There are {0} processes and {1} resource types in the system.\n", state.num_processes(), state.num_resources())?;
 
-    //print the header message specifying the matrix type
-    eprintln!("The {} Matrix is: ", name);
-    eprint!("   ");
+    //print resource and available vectors, and max, allocation and need matrices
+    print_state(out, &state, style)?;
 
-    //print the resource labels
-    for i in 0..labels.len() {
+    //if tracing, show how the safety algorithm reached its verdict
+    if trace {
+        print_trace(out, &state.trace_safety_with(policy).expect("the policy was checked by 'load_scenario'").1, state.names(), style)?;
+    }
 
-        //handle shifting/formatting for values above 9
-        for j in 0..matrix.len() {
-            if matrix[j][i] > 9 {
-                shift[i] = true;
-                eprint!(" ");
-                break;
-            }
+    //if the system is not in a safe state, indicate so and explain why
+    let safe_sequence = match state.check_safety_with(policy).expect("the policy was checked by 'load_scenario'") {
+        Safety::Safe(safe_sequence) => safe_sequence,
+        Safety::Unsafe(report) => {
+            writeln!(out, "THE SYSTEM IS NOT IN A SAFE STATE.")?;
+            print_unsafe_report(out, &report, state.names(), style)?;
+            return Ok(ExitStatus::Unsafe);
         }
+    };
 
-        eprint!("{} ", labels[i]);
+    //otherwise, print the safe sequence, naming the policy that ordered it
+    //unless it is the classic lowest-index one
+    writeln!(out, "THE SYSTEM IS IN A SAFE STATE.")?;
+    if *policy != SelectionPolicy::LowestIndex {
+        writeln!(out, "Selection policy: {}", policy.name())?;
     }
-    eprintln!("");
+    print_safe_sequence(out, &safe_sequence, state.names())?;
+
+    //apply each request and release in order, printing the new
+    //state when it is granted
+    let mut status = ExitStatus::Granted;
+    for (i, event) in events.iter().enumerate() {
+        let name = event.kind.name().to_uppercase();
+        let process = state.names().process_row(event.process_id);
+        print_vector(out, &event.vector, event.kind.name(), &resource_labels, Some(&process), style)?;
+
+        //if tracing a request, show the safety algorithm run on the
+        //state the request would leave, when it gets that far
+        if trace && event.kind == EventKind::Request {
+            if let Some(steps) = request_trace(&state, event, policy) {
+                print_trace(out, &steps, state.names(), style)?;
+            }
+        }
 
-    //print the matrix, with each row labeled by the process number
-    for i in 0..matrix.len() {
-        eprint!("{}: ", i);
-        for j in 0..matrix[i].len() {
+        let outcome = state.apply_event(event);
+        if outcome.is_granted() {
+            writeln!(out, "THE {} CAN BE GRANTED: NEW STATE FOLLOWS\n", name)?;
+            print_state(out, &state, style)?;
+        }
+        else {
+            writeln!(out, "THE {} CANNOT BE GRANTED.", name)?;
+            if status == ExitStatus::Granted {
+                status = ExitStatus::from_outcome(&outcome);
+            }
+            if let Some(reason) = outcome.reason(state.names()) {
+                writeln!(out, "Reason: {}", reason)?;
+            }
 
-            //handle formatting for values above 9 before printing the value
-            if shift[j] && matrix[i][j] < 10 {
-                eprint!(" ");
+            //separate the denial from the next event
+            if i + 1 < events.len() {
+                writeln!(out)?;
             }
+        }
+    }
+    Ok(status)
+}
 
-            eprint!("{} ", matrix[i][j]);
+//*********************************************************
+//
+// JSON Banker's Algorithm Function
+//
+// Runs the Banker's Algorithm like the Banker's Algorithm
+// Function, but writes one JSON document instead of the
+// printed states. The document holds the
+// names, the initial state (including its need matrix),
+// the selection policy, the safety verdict (with the safe
+// sequence the policy ordered), the decision on each request and
+// release, and the final state. With tracing, it also
+// holds the steps of the safety algorithm for the initial
+// state and for each request that reaches the safety check.
+//
+// Return Value
+// ------------
+// io::Result<ExitStatus>   how the run ended (the first denial, if any event
+//                          was denied), or the error encountered while writing
+//
+// Function Parameters
+// -------------------
+// out               dyn Write        reference  where to write the document
+// state             BankerState      value      state of the system read from the file
+// events            Vec<Event>       reference  requests and releases to apply, in order
+// trace             bool             value      whether to include the steps of each safety check
+// policy            SelectionPolicy  reference  how the safety algorithm picks the next process to finish
+//
+// Local Variables
+// ---------------
+// initial_state     Json            initial state, before any event is applied
+// safety            Safety          safety of the initial state
+// steps             Vec<TraceStep>  steps of the safety algorithm for the initial state
+// request_steps     Option<Vec>     steps of the safety algorithm for the current request, if it got that far
+// decisions         Vec<Json>       each event and its outcome
+// event             Event           current request or release
+// outcome           EventOutcome    whether the event was granted, and if not, why
+// status            ExitStatus      how the run has ended so far
+// members           Vec             members of the document
+// document          Json            the whole document
+//
+//**********************************************************
+fn run_banker_json(out: &mut dyn Write, mut state: BankerState, events: &[Event], trace: bool,
+                   policy: &SelectionPolicy) -> io::Result<ExitStatus> {
+    let initial_state = state_to_json(&state);
+    let (safety, steps) = state.trace_safety_with(policy).expect("the policy was checked by 'load_scenario'");
+
+    //events are only applied to a state that starts out safe
+    let mut decisions = vec![];
+    let mut status = match safety.is_safe() {
+        true => ExitStatus::Granted,
+        false => ExitStatus::Unsafe,
+    };
+    if safety.is_safe() {
+        for event in events {
+            let request_steps = match trace && event.kind == EventKind::Request {
+                true => request_trace(&state, event, policy),
+                false => None,
+            };
+            let outcome = state.apply_event(event);
+            if status == ExitStatus::Granted {
+                status = ExitStatus::from_outcome(&outcome);
+            }
+            let mut decision = vec![
+                ("kind", Json::String(event.kind.name().to_lowercase())),
+                ("process", Json::Number(event.process_id as i128)),
+                ("vector", Json::vector(&event.vector)),
+                ("decision", outcome_to_json(&outcome, state.names())),
+            ];
+            if trace {
+                decision.push(("trace", request_steps.as_deref().map_or(Json::Null, trace_to_json)));
+            }
+            decisions.push(Json::object(decision));
         }
-        eprintln!("");
     }
-    eprintln!("");
+
+    let mut members = vec![
+        ("processes", Json::Number(state.num_processes() as i128)),
+        ("resource_types", Json::Number(state.num_resources() as i128)),
+        ("names", names_to_json(&state)),
+        ("initial_state", initial_state),
+        ("policy", policy_to_json(policy)),
+        ("safety", safety_to_json(&safety)),
+    ];
+    if trace {
+        members.push(("trace", trace_to_json(&steps)));
+    }
+    members.push(("events", Json::Array(decisions)));
+    members.push(("final_state", state_to_json(&state)));
+    let document = Json::object(members);
+    writeln!(out, "{}", document)?;
+    Ok(status)
 }
 
 //*********************************************************
 //
-// Read First Line Function
+// Read Scenario Function
 //
-// Reads the first line of the file and returns the number
-// of processes and the number of resources.
+// Reads the whole input, from the file at 'path' or from
+// stdin if 'path' is '-', as JSON or in the text format.
 //
-// Return Values (both returned as a tuple)
+// Return Value
 // ------------
-// usize      number of processes
-// usize      number of resources
+// Result<ParsedInput, ParseError>   everything read, or the first error encountered
 //
 // Function Parameters
 // -------------------
-// reader   BufReader<File>  reference  file reader
+// path         &str   reference  path of the input file, or '-' for stdin
+// json_input   bool   value      whether to read the input as JSON
 //
 // Local Variables
 // ---------------
-// line           String     line read from file
-// parts          Vec<&str>  vector of line parts containing the number of processes and resources
-// num_processes  usize      number of processes
-// num_resources  usize      number of resources
+// io_error     closure   converts an I/O error into a ParseError for the input
+//
+//*********************************************************
+fn read_scenario(path: &str, json_input: bool) -> Result<ParsedInput, ParseError> {
+    let name = if path == "-" { STDIN_NAME } else { path };
+    let io_error = |error: io::Error| ParseError::Io { file: name.to_string(), message: error.to_string() };
+
+    match (path == "-", json_input) {
+        (true, true) => io::read_to_string(io::stdin()).map_err(io_error).and_then(|text| read_json_input(&text, name)),
+        (true, false) => read_input(&mut LineReader::new(io::stdin().lock(), name)),
+        (false, true) => fs::read_to_string(path).map_err(io_error).and_then(|text| read_json_input(&text, name)),
+        (false, false) => File::open(path)
+            .map_err(io_error)
+            .and_then(|file| read_input(&mut LineReader::new(BufReader::new(file), name))),
+    }
+}
+
+//removes 'flag' from the arguments, returning whether it was present
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    match args.iter().position(|arg| arg == flag) {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+//removes 'option' and the value following it from the arguments, returning the value;
+//an option given last, or followed by another option, is missing its value
+fn take_option(args: &mut Vec<String>, option: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == option)?;
+    args.remove(index);
+    if args.get(index).is_none_or(|value| value.starts_with("--")) {
+        eprintln!("error: '{}' needs a value", option);
+        ExitStatus::Usage.exit();
+    }
+    Some(args.remove(index))
+}
+
+//*********************************************************
+//
+// Help Texts
+//
+// The '--help' text of the program and of each subcommand.
 //
 //*********************************************************
-fn read_first_line(reader: &mut BufReader<File>) -> (usize, usize) {
+const MAIN_HELP: &str = "\
+Usage: banker [<command>] [options] [arguments]
+
+Commands:
+  check      check whether the state in a file is safe
+  detect     find the processes that are deadlocked, given what each is waiting for
+  request    try a request against the state in a file
+  release    release resources held by a process
+  simulate   apply the requests and releases in a file, in order (the default)
+  sequences  list or count every safe sequence of the state in a file
+  convert    translate a file between the text and JSON formats
+  generate   generate a random safe state
+  repl       explore the state in a file interactively
+  help       print the help of a command
+
+Run 'banker <command> --help' for the arguments and options of a command.
+Without a command, 'banker [options] [<input file>]' runs 'simulate'.";
+
+const INPUT_OPTIONS: &str = "\
+Input:
+  <input file>              file to read, or '-' (or nothing) to read stdin
+  --input-format text|json  read the input as text or JSON (default: JSON for '.json' files)
+  --lenient                 report inconsistencies in the state as warnings instead of errors";
+
+const OUTPUT_OPTIONS: &str = "\
+Output:
+  --format text|json        write the results as text (to stderr) or JSON (to stdout)
+  --borders                 draw borders around vectors and matrices
+  --trace                   show each step of the safety algorithm (the Work/Finish table)
+  --policy <policy>         which process the safety algorithm lets finish next when several
+                            can, so the safe sequence is a recommended execution order:
+                              lowest-index        the lowest process ID (the default)
+                              smallest-need       the smallest remaining need
+                              largest-allocation  the largest allocation, released first
+                              priority            the highest of '--priorities <values>'
+                              deadline            the earliest of '--deadlines <values>'
+  --priorities <values>     priority of each process (larger is more urgent)
+  --deadlines <values>      deadline of each process
+  --output <file>           write the results to <file> instead
+  -h, --help                print this help";
+
+const CONVERT_OPTIONS: &str = "\
+Output:
+  --to text|json            format to write
+  --output <file>           write to <file> instead of stdout
+  -h, --help                print this help";
+
+const CHECK_HELP: &str = "\
+Usage: banker check [options] [<input file>]
+
+Prints the state in the input file and whether it is safe, with a safe
+sequence if it is. Requests and releases in the file are ignored.";
+
+const DETECT_HELP: &str = "\
+Usage: banker detect [options] [<input file>]
+
+Runs the deadlock detection algorithm on the Available vector, the
+Allocation matrix and the Request matrix of what each process is blocked
+waiting for, and prints the processes that are deadlocked, if any. The
+Request matrix follows the Allocation matrix after a 'requests:' line
+(the 'requests' key in JSON input). Exits with 8 if some process is
+deadlocked.";
+
+const REQUEST_HELP: &str = "\
+Usage: banker request [options] <input file> <process> <values>...
+
+Tries a request by <process> (a name, an ID such as 2, or P2) for
+<values> (one per resource type) against the state in the input file,
+without editing the file. Requests and releases in the file are ignored.";
+
+const RELEASE_HELP: &str = "\
+Usage: banker release [options] <input file> <process> <values>...
+
+Releases <values> (one per resource type) held by <process> (a name, an
+ID such as 2, or P2) in the state in the input file, without editing the
+file. Requests and releases in the file are ignored.";
+
+const SIMULATE_HELP: &str = "\
+Usage: banker simulate [options] [<input file>]
+
+Prints the state in the input file and whether it is safe, then applies
+each request and release in the file in order, printing the new state
+after each one that is granted.";
+
+const SEQUENCES_HELP: &str = "\
+Usage: banker sequences [options] [<input file>] [--limit <n>] [--count]
+
+Prints the state in the input file and every safe sequence of it (in
+lexicographic order, up to <n> of them, default 100), followed by the
+number of safe sequences, or 'at least <n>' if there are more than <n>.
+With '--count', only the number is printed; it is counted exactly
+without listing the sequences, for up to 25 processes, which takes
+seconds from about 20 processes on. The more safe sequences a state has,
+the more orders its processes can finish in. Requests and releases in
+the file are ignored.";
+
+const CONVERT_HELP: &str = "\
+Usage: banker convert [options] [<input file>] [--to text|json]
+
+Writes the state and the requests and releases in the input file in the
+other format (or the format given with '--to') to stdout, or to the file
+given with '--output'. Converting a text file to text normalizes it.";
+
+const REPL_HELP: &str = "\
+Usage: banker repl [options] <input file>
+
+Loads the state in the input file and reads commands from stdin, one per
+line, keeping the state in memory between commands. Type 'help' in the
+session for the commands. Requests and releases in the file are ignored.
+
+Options:
+  --input-format text|json  read the input as text or JSON (default: JSON for '.json' files)
+  --lenient                 report inconsistencies in the state as warnings instead of errors
+  --borders                 draw borders around vectors and matrices
+  --policy <policy>         how 'sequence' and 'trace' pick the next process to finish
+                            (see 'banker check --help'), with '--priorities <values>'
+                            or '--deadlines <values>' where the policy needs them
+  -h, --help                print this help";
+
+const GENERATE_HELP: &str = "\
+Usage: banker generate [--processes <n>] [--resources <m>] [--seed <seed>] [--to text|json]
+
+Writes a random safe state with <n> processes (default 5) and <m>
+resource types (default 3) in the text format (or the format given with
+'--to') to stdout, or to the file given with '--output'. The same seed
+always gives the same state.
+
+Options:
+  --output <file>           write the state to <file>
+  -h, --help                print this help";
+
+//prints the help of 'command' (the program's help if it is not a command)
+fn print_help(command: &str) {
+    let (text, input, output) = match command {
+        "check" => (CHECK_HELP, true, OUTPUT_OPTIONS),
+        "detect" => (DETECT_HELP, true, OUTPUT_OPTIONS),
+        "request" => (REQUEST_HELP, true, OUTPUT_OPTIONS),
+        "release" => (RELEASE_HELP, true, OUTPUT_OPTIONS),
+        "simulate" => (SIMULATE_HELP, true, OUTPUT_OPTIONS),
+        "sequences" => (SEQUENCES_HELP, true, OUTPUT_OPTIONS),
+        "convert" => (CONVERT_HELP, true, CONVERT_OPTIONS),
+        "generate" => (GENERATE_HELP, false, ""),
+        "repl" => (REPL_HELP, false, ""),
+        _ => (MAIN_HELP, false, ""),
+    };
+    let mut help = text.to_string();
+    if input {
+        help = format!("{}\n\n{}", help, INPUT_OPTIONS);
+    }
+    if !output.is_empty() {
+        help = format!("{}\n\n{}", help, output);
+    }
 
-    //initialize the line reader and read the first line in full
-    let mut line = String::new();
-    reader.read_line(&mut line).unwrap();
+    //a closed stdout (e.g. piped into 'head') is not an error worth reporting
+    let _ = writeln!(io::stdout(), "{}", help);
+}
 
-    //initialize 'parts' to hold the line parts containing
-    //the number of processes and resources
-    let parts: Vec<&str> = line.trim().split(' ').collect();
+//prints a usage error for 'command' and ends the program
+fn usage_error(command: &str, message: &str) -> ! {
+    eprintln!("error: {}", message);
+    eprintln!("Run 'banker {} --help' for usage.", command);
+    ExitStatus::Usage.exit();
+}
 
-    //read and store the number of processes and resources
-    let num_processes = parts[0].parse().unwrap();
-    let num_resources = parts[1].parse().unwrap();
-    (num_processes, num_resources)
+//*********************************************************
+//
+// Options Struct
+//
+// The options given on the command line, shared by the
+// subcommands.
+//
+// Fields
+// ------
+// mode           CheckMode        strict (default) or lenient checking of the state
+// style          TableStyle       plain (default) or bordered tables
+// json           bool             whether to write JSON instead of text
+// input_format   Option<String>   input format given on the command line, if any
+// output         Option<String>   file to write the results to, if any
+// to             Option<String>   format to convert or generate to, if given
+// trace          bool             whether to print the steps of each safety check
+// policy         SelectionPolicy  how the safety algorithm picks the next process to finish
+// help           bool             whether '--help' was given
+//
+//*********************************************************
+struct Options {
+    mode: CheckMode,
+    style: TableStyle,
+    json: bool,
+    input_format: Option<String>,
+    output: Option<String>,
+    to: Option<String>,
+    trace: bool,
+    policy: SelectionPolicy,
+    help: bool,
 }
 
 //*********************************************************
 //
-// Read Resource Vector Function
+// Parse Options Function
 //
-// Reads the resource vector from the file and returns it,
-// along with the resource labels.
+// Removes the options shared by the subcommands from the
+// arguments, leaving the command and its arguments.
 //
-// Return Values (both returned as a tuple)
+// Return Value
 // ------------
-// Vec<char>      vector of resource labels
-// Vec<i32>       vector of resource values
+// Options   the options given
 //
 // Function Parameters
 // -------------------
-// reader   BufReader<File>  reference  file reader
+// args      Vec<String>   reference  command line arguments
 //
 // Local Variables
 // ---------------
-// line             String     line read from file
-// resource_labels  Vec<char>  vector of resource labels
-// resource_vec     Vec<i32>   vector of resource values
-// i                usize      used to create a resource label for each resource type
-// part             &str       line part containing a resource value
-// label            char       resource label to be stored in 'resource_labels'
-//
-//*********************************************************
-fn read_resource_vector(reader: &mut BufReader<File>) -> (Vec<char>, Vec<i32>) {
-
-    //initialize the line reader and skip over the blank line
-    let mut line = String::new();
-    reader.read_line(&mut line).unwrap();
-    line.clear();
-    reader.read_line(&mut line).unwrap();
-
-    //initialize the vectors that will stores resource labels and values
-    let mut resource_labels = vec![];
-    let mut resource_vec: Vec<i32> = vec![];
-
-    //read the resource values, create a label for each resource type
-    //and store them in their respective vectors
-    //the resource labels are stored as characters, starting with 'A'
-    for (i, part) in line.trim().split(' ').enumerate() {
-        let label = ((i as u8) + b'A') as char;
-        resource_labels.push(label);
-        resource_vec.push(part.parse().unwrap());
-    }
-    (resource_labels, resource_vec)
+// format    Option<String>   a format given with '--format', '--input-format' or '--to'
+// policy    SelectionPolicy  the policy given with '--policy', and its values
+//
+//*********************************************************
+fn parse_options(args: &mut Vec<String>) -> Options {
+    let mode = match take_flag(args, "--lenient") {
+        true => CheckMode::Lenient,
+        false => CheckMode::Strict,
+    };
+    let style = match take_flag(args, "--borders") {
+        true => TableStyle::Bordered,
+        false => TableStyle::Plain,
+    };
+    let trace = take_flag(args, "--trace");
+    let help = take_flag(args, "--help") | take_flag(args, "-h");
+
+    //every format is either 'text' or 'json'
+    let mut formats = vec![];
+    for option in ["--format", "--input-format", "--to"] {
+        let format = take_option(args, option);
+        if let Some(format) = format.as_deref().filter(|format| !matches!(*format, "text" | "json")) {
+            eprintln!("error: unknown format '{}' for '{}' (expected 'text' or 'json')", format, option);
+            ExitStatus::Usage.exit();
+        }
+        formats.push(format);
+    }
+    let to = formats.pop().flatten();
+    let input_format = formats.pop().flatten();
+    let json = formats.pop().flatten().as_deref() == Some("json");
+
+    let policy = parse_policy(args);
+
+    Options { mode, style, json, input_format, output: take_option(args, "--output"), to, trace, policy, help }
 }
 
 //*********************************************************
 //
-// Read Available Vector Function
+// Parse Policy Function
 //
-// Reads the available vector from the file and returns it.
+// Removes '--policy' and the values the priority and
+// deadline policies take ('--priorities' and '--deadlines')
+// from the arguments, ending the program if the policy is
+// unknown or its values are missing, not integers, or
+// given for another policy.
 //
 // Return Value
 // ------------
-// Vec<i32>      vector of available resources
+// SelectionPolicy   the policy given (lowest-index if none is)
 //
 // Function Parameters
 // -------------------
-// reader   BufReader<File>  reference  file reader
+// args      Vec<String>   reference  command line arguments
 //
 // Local Variables
 // ---------------
-// line             String     line read from file
-// available_vec    Vec<i32>   vector of available resources
-// part             &str       used to parse each part of the line
+// name         Option<String>   policy given with '--policy', if any
+// priorities   Option<String>   values given with '--priorities', if any
+// deadlines    Option<String>   values given with '--deadlines', if any
+// values       closure          reads the values given with an option (comma or space separated)
 //
 //*********************************************************
-fn read_available_vector(reader: &mut BufReader<File>) -> Vec<i32> {
-
-    //initialize the line reader and skip over the blank line
-    let mut line = String::new();
-    reader.read_line(&mut line).unwrap();
-    line.clear();
-    reader.read_line(&mut line).unwrap();
-
-    //initialize the vector that will store the available resources
-    let mut available_vec: Vec<i32> = vec![];
+fn parse_policy(args: &mut Vec<String>) -> SelectionPolicy {
+    let name = take_option(args, "--policy");
+    let priorities = take_option(args, "--priorities");
+    let deadlines = take_option(args, "--deadlines");
+    let values = |option: &str, values: Option<String>| -> Vec<i32> {
+        let Some(values) = values else {
+            eprintln!("error: the '{}' policy needs '{} <values>', one per process", name.as_deref().unwrap_or_default(), option);
+            ExitStatus::Usage.exit();
+        };
+        values.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse().unwrap_or_else(|_| {
+                eprintln!("error: '{}' needs integers, found '{}'", option, value);
+                ExitStatus::Usage.exit();
+            }))
+            .collect()
+    };
+
+    let policy = match name.as_deref() {
+        None | Some("lowest-index") => SelectionPolicy::LowestIndex,
+        Some("smallest-need") => SelectionPolicy::SmallestNeed,
+        Some("largest-allocation") => SelectionPolicy::LargestAllocation,
+        Some("priority") => SelectionPolicy::Priority(values("--priorities", priorities.clone())),
+        Some("deadline") => SelectionPolicy::Deadline(values("--deadlines", deadlines.clone())),
+        Some(other) => {
+            eprintln!("error: unknown policy '{}' (expected 'lowest-index', 'smallest-need', 'largest-allocation', \
+                       'priority' or 'deadline')", other);
+            ExitStatus::Usage.exit();
+        }
+    };
 
-    //read the available resource values and store them in the vector
-    for part in line.trim().split(' ') {
-        available_vec.push(part.parse().unwrap());
+    //values only make sense for the policy that uses them
+    for (option, given, needed_by) in [("--priorities", &priorities, "priority"), ("--deadlines", &deadlines, "deadline")] {
+        if given.is_some() && policy.name() != needed_by {
+            eprintln!("error: '{}' is only used with '--policy {}'", option, needed_by);
+            ExitStatus::Usage.exit();
+        }
     }
-    available_vec
+    policy
 }
 
 //*********************************************************
 //
-// Read Matrix Function
+// Load Scenario Function
 //
-// Reads a matrix from the file and returns it.
+// Reads the input, checks that its vectors and matrices
+// match the declared dimensions, that no event is for a
+// negative amount, and that the resulting
+// state is consistent, and builds the state. The values
+// of a priority or deadline policy are checked against
+// the number of processes here too. Errors are
+// printed as diagnostics and end the program; in lenient
+// mode, inconsistencies are printed as warnings instead.
 //
-// Return Value
+// Return Value (both returned as a tuple)
 // ------------
-// Vec<Vec<i32>>      matrix of integers
+// BankerState    state of the system read from the input
+// Vec<Event>     requests and releases read from the input
 //
 // Function Parameters
 // -------------------
-// reader         BufReader<File>  reference  file reader
-// num_processes  usize            value      number of processes
+// path      &str      reference  path of the input file, or '-' for stdin
+// options   Options   reference  options given on the command line
 //
 // Local Variables
 // ---------------
-// matrix    Vec<Vec<i32>>  matrix of integers, holds the matrix to be returned
-// line      String         used to read lines/rows from the file
-// row       Vec<i32>       used to process/store each row of the matrix
-// part      &str           used to parse individual parts of each line
+// json_input   bool              whether to read the input as JSON
+// input        ParsedInput       input read from the file
+// errors       Vec<ParseError>   dimension mismatches found in the input
+// events       Vec<Event>        requests and releases read from the file
+// state        BankerState       state of the system read from the file
 //
-//************************************************************
-fn read_matrix(reader: &mut BufReader<File>, num_processes: usize) -> Vec<Vec<i32>> {
+//*********************************************************
+fn load_scenario(path: &str, options: &Options) -> (BankerState, Vec<Event>) {
+
+    //JSON input is chosen with '--input-format json' or a '.json' extension
+    let json_input = match options.input_format.as_deref() {
+        Some(format) => format == "json",
+        None => is_json_path(path),
+    };
+
+    //read the whole input in the chosen format
+    let input = match read_scenario(path, json_input) {
+        Ok(input) => input,
+        Err(error) => {
+            print_parse_error(&error);
+            ExitStatus::from_parse_error(&error).exit();
+        }
+    };
+
+    //check that the vectors and matrices match the declared dimensions,
+    //and that no event is for a negative amount
+    let mut errors = check_dimensions(&input);
+    errors.extend(check_event_values(&input));
+    if !errors.is_empty() {
+        for error in &errors {
+            print_parse_error(error);
+            eprintln!();
+        }
+        eprintln!("error: aborting due to {} previous error(s)", errors.len());
+        ExitStatus::InvalidInput.exit();
+    }
 
-    //initialize the matrix that stores our final result
-    let mut matrix = vec![];
+    //build the state of the system (need matrix is computed on construction)
+    let events: Vec<Event> = input.events.iter().map(EventRow::to_event).collect();
+    let state = input.into_state();
 
-    //initialize the line reader and skip over the blank line
-    let mut line: String = String::new();
-    reader.read_line(&mut line).unwrap();
+    //check that the state is consistent, rejecting it in strict mode
+    //and warning about it in lenient mode
+    match check_state(&state, options.mode) {
+        Ok(warnings) => {
+            for warning in &warnings {
+                eprintln!("warning: {}", warning.describe(state.names()));
+            }
+            if !warnings.is_empty() {
+                eprintln!();
+            }
+        }
+        Err(violations) => {
+            for violation in &violations {
+                eprintln!("error: {}", violation.describe(state.names()));
+            }
+            //'--lenient' does not help when the values overflow
+            match violations.iter().any(InvariantViolation::is_fatal) {
+                true => eprintln!("error: aborting due to {} inconsistencies in the state", violations.len()),
+                false => eprintln!("error: aborting due to {} inconsistencies in the state (use --lenient to continue anyway)",
+                                   violations.len()),
+            }
+            ExitStatus::InvalidInput.exit();
+        }
+    }
 
-    //store each line in the file as a row in the matrix
-    //stopping when we reach the number of processes
-    for _ in 0..num_processes {
+    //a priority or deadline is needed for every process
+    if let Err(error) = options.policy.check(state.num_processes()) {
+        eprintln!("error: {} (one per process)", error);
+        ExitStatus::Usage.exit();
+    }
+    (state, events)
+}
 
-        //initialize the row vector to store the next line
-        let mut row: Vec<i32> = vec![];
+//whether 'path' names a JSON file
+fn is_json_path(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| extension == "json")
+}
 
-        //read the next line and parse it into a row of values
-        //then store the row in the matrix
-        line.clear();
-        reader.read_line(&mut line).unwrap();
+//opens where the results go: the '--output' file, or else stdout or stderr
+fn open_output(options: &Options, stdout: bool) -> Box<dyn Write> {
+    match &options.output {
+        Some(output) => match File::create(output) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(error) => {
+                print_parse_error(&ParseError::Io { file: output.clone(), message: error.to_string() });
+                ExitStatus::IoError.exit();
+            }
+        },
+        None if stdout => Box::new(io::stdout()),
+        None => Box::new(io::stderr()),
+    }
+}
 
-        //parse each part of the line into an integer and store it in the row
-        for part in line.trim().split(' ') {
-            row.push(part.parse().unwrap());
+//flushes the results and ends the program with the status of the run
+fn finish(mut out: Box<dyn Write>, result: io::Result<ExitStatus>) -> ! {
+    match result.and_then(|status| out.flush().map(|_| status)) {
+        Ok(status) => status.exit(),
+        Err(error) => {
+            eprintln!("error: cannot write the results: {}", error);
+            ExitStatus::IoError.exit();
         }
+    }
+}
 
-        //add the row to the matrix
-        matrix.push(row);
+//the input file given in 'args' ('-' for stdin if there is none), which may hold at most 'max' arguments
+fn input_path(command: &str, args: &[String], max: usize) -> String {
+    if args.len() > max {
+        usage_error(command, &format!("unexpected argument '{}'", args[max]));
     }
-    matrix
+    args.first().cloned().unwrap_or_else(|| "-".to_string())
+}
+
+//runs the algorithm on 'state', applying 'events', and ends the program
+fn run(state: BankerState, events: &[Event], options: &Options) -> ! {
+    let mut out = open_output(options, options.json);
+    let result = match options.json {
+        true => run_banker_json(&mut out, state, events, options.trace, &options.policy),
+        false => run_banker(&mut out, state, events, options.style, options.trace, &options.policy),
+    };
+    finish(out, result);
 }
 
 //*********************************************************
 //
-// Compute Need Matrix Function
+// Detect Command Function
 //
-// Computes the need matrix from the max matrix and the
-// allocation matrix and returns it.
+// Runs the 'detect' subcommand: prints the available
+// vector and the allocation and request matrices of the
+// state in the input file, then the processes that the
+// deadlock detection algorithm finds deadlocked (or, with
+// '--format json', writes all of it as one JSON document).
 //
 // Return Value
 // ------------
-// Vec<Vec<i32>>      need matrix
+// none (the program ends with the code of an ExitStatus)
 //
 // Function Parameters
 // -------------------
-// num_processes      usize            value      number of processes
-// num_resources      usize            value      number of resources
-// max_matrix         Vec<Vec<i32>>    reference  max matrix
-// allocation_matrix  Vec<Vec<i32>>    reference  allocation matrix
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
 //
 // Local Variables
 // ---------------
-// need_matrix  Vec<Vec<i32>>  used to store the need matrix to be returned
-// i            usize          loop counter for processes
-// j            usize          loop counter for resource types
-// row          Vec<i32>       loop variable that is used to store each row of the need matrix
+// state            BankerState     state of the system read from the file
+// request_matrix   Vec<Vec<i32>>   what each process is blocked waiting for
+// detection        Detection       result of the detection algorithm
+// status           ExitStatus      Deadlocked if some process is deadlocked
+// out              dyn Write       where to print the results
 //
 //*********************************************************
-fn compute_need_matrix(num_processes: usize, num_resources: usize, max_matrix: &Vec<Vec<i32>>, allocation_matrix: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-
-    //initialize the matrix that stores our final result
-    let mut need_matrix = vec![];
-
-    //compute the need matrix, row by row (process by process)
-    for i in 0..num_processes {
-
-        //initialize a row of the need matrix for the current process
-        let mut row = vec![];
-
-        //compute and store the process need for each resource 
-        //(max - allocation)
-        for j in 0..num_resources {
-            row.push(max_matrix[i][j] - allocation_matrix[i][j]);
+fn detect_command(args: &[String], options: &Options) -> ! {
+    let (state, _) = load_scenario(&input_path("detect", args, 1), options);
+    let request_matrix = match state.request_matrix() {
+        Some(request_matrix) => request_matrix,
+        None => {
+            eprintln!("error: the input has no Request matrix (add a 'requests:' line after the Allocation matrix, \
+                       followed by one row per process)");
+            ExitStatus::InvalidInput.exit();
+        }
+    };
+
+    let detection = detect_deadlock(state.available_vec(), state.allocation_matrix(), request_matrix)
+        .expect("the Request matrix was checked by 'check_dimensions'");
+    let status = match detection.is_deadlocked() {
+        true => ExitStatus::Deadlocked,
+        false => ExitStatus::Granted,
+    };
+
+    let mut out = open_output(options, options.json);
+    let result = match options.json {
+        true => {
+            let document = Json::object(vec![
+                ("processes", Json::Number(state.num_processes() as i128)),
+                ("resource_types", Json::Number(state.num_resources() as i128)),
+                ("names", names_to_json(&state)),
+                ("available", Json::vector(state.available_vec())),
+                ("allocation", Json::matrix(state.allocation_matrix())),
+                ("requests", Json::matrix(request_matrix)),
+                ("detection", detection_to_json(&detection)),
+            ]);
+            writeln!(out, "{}", document)
         }
+        false => print_detect(&mut out, &state, request_matrix, &detection, options.style),
+    };
+    finish(out, result.map(|_| status));
+}
 
-        //add the row to the need matrix
-        need_matrix.push(row);
-    }
-    need_matrix
+//prints the input of the detection algorithm and its verdict
+fn print_detect(out: &mut dyn Write, state: &BankerState, request_matrix: &[Vec<i32>], detection: &Detection,
+                style: TableStyle) -> io::Result<()> {
+    let resource_labels = state.names().resource_labels(state.num_resources());
+    let process_labels = state.names().process_rows(state.num_processes());
+    writeln!(out, "There are {0} processes and {1} resource types in the system.\n", state.num_processes(), state.num_resources())?;
+    print_vector(out, state.available_vec(), "Available", &resource_labels, None, style)?;
+    print_matrix(out, state.allocation_matrix(), "Allocation", &resource_labels, &process_labels, style)?;
+    print_matrix(out, request_matrix, "Request", &resource_labels, &process_labels, style)?;
+    print_detection(out, detection, request_matrix, state.names(), style)
 }
 
 //*********************************************************
 //
-// Fulfill Request Function
+// Event Command Function
 //
-// Determines if a resource request can be fulfilled and 
-// if so, fulfills it.
+// Runs the 'request' and 'release' subcommands: applies
+// one request or release given on the command line to the
+// state in the input file, ignoring the file's own events.
 //
 // Return Value
 // ------------
-// bool    true if request can be fulfilled, false if not
+// none (the program ends with the code of an ExitStatus)
 //
 // Function Parameters
 // -------------------
-// process_id        usize            value      process ID of the requesting process
-// request_vec       Vec<i32>         reference  request vector
-// num_resources     usize            value      number of resource types
-// available_vec     Vec<i32>         reference  available vector
-// allocation_matrix Vec<Vec<i32>>    reference  allocation matrix
-// need_matrix       Vec<Vec<i32>>    reference  need matrix
+// kind      EventKind     value      request or release
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
 //
 // Local Variables
 // ---------------
-// i  usize  loop counter for resources
+// command      &str          name of the subcommand
+// state        BankerState   state of the system read from the file
+// process_id   usize         process ID of the requesting/releasing process
+// vector       Vec<i32>      instances of each resource type requested/released
 //
 //*********************************************************
-fn fulfill_request(process_id: usize, request_vec: &Vec<i32>, num_resources: usize, available_vec: &mut Vec<i32>, 
-                    allocation_matrix: &mut Vec<Vec<i32>>, need_matrix: &mut Vec<Vec<i32>>) -> bool {
-
-    //determine if the request can be fulfilled
-    for i in 0..num_resources {
-
-        //If the request exceeds the process need, this is in invalid 
-        //request as the process cannot request more than it has declared
-        //it needs
-        if request_vec[i] > need_matrix[process_id][i] {
-            return false;
-        }
-
-        //If the request exceeds the available resources, this is an
-        //invalid request as the system does not have enough resources
-        //to fulfill it
-        if request_vec[i] > available_vec[i] {
-            return false;
+fn event_command(kind: EventKind, args: &[String], options: &Options) -> ! {
+    let command = kind.name().to_lowercase();
+    if args.len() < 3 {
+        usage_error(&command, "expected an input file, a process and the values");
+    }
+    let (state, _) = load_scenario(&args[0], options);
+
+    //a process that does not exist is refused like an event for one in the input file
+    let process_id = match state.names().find_process(&args[1]) {
+        Some(process_id) if process_id < state.num_processes() => process_id,
+        _ => {
+            eprintln!("error: '{}' is not a process of the state, which has {} processes", args[1], state.num_processes());
+            ExitStatus::InvalidInput.exit();
         }
+    };
+
+    //the values may be given as separate arguments or as one quoted argument
+    let vector: Vec<i32> = args[2..].iter()
+        .flat_map(|arg| arg.split_whitespace())
+        .map(|value| value.parse().unwrap_or_else(|_| usage_error(&command, &format!("'{}' is not an integer", value))))
+        .collect();
+    if vector.len() != state.num_resources() {
+        usage_error(&command, &format!("expected {} values, one per resource type, found {}", state.num_resources(), vector.len()));
     }
 
-    //if we have made it to this part, fulfill the request
-    //Refer to the slides detailing the Banker's Algorithm
-    //for how requests are fulfilled
-    for i in 0..num_resources {
-        available_vec[i] -= request_vec[i];
-        allocation_matrix[process_id][i] += request_vec[i];
-        need_matrix[process_id][i] -= request_vec[i];
-    }
-    true
+    run(state, &[Event { kind, process_id, vector }], options);
 }
 
 //*********************************************************
 //
-// Read Request Vector Function
+// Sequences Command Function
 //
-// Reads the request vector from the file and returns the 
-// requesting process id and the request vector as a tuple.
+// Runs the 'sequences' subcommand: prints the state in the
+// input file, its safe sequences (up to '--limit', or none
+// with '--count') and the number of them. An unsafe state
+// is explained as by 'check' instead.
 //
-// Return Values (both returned as a tuple)
+// Return Value
 // ------------
-// usize    the process id
-// Vec<i32> the request vector
+// none (the program ends with the code of an ExitStatus)
 //
 // Function Parameters
 // -------------------
-// reader   BufReader<File>  reference  file reader
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
 //
 // Local Variables
 // ---------------
-// line         String    stores the line read from the file
-// request_vec  Vec<i32>  stores the request vector
-// process_id   usize     stores the process id of the requesting process
-// i            usize     loop counter for parts of the line
-// part         &str      used to store each part of the line
+// count_only   bool              whether to count the sequences without listing them
+// limit        usize             most sequences to list
+// state        BankerState       state of the system read from the file
+// safe         bool              whether the state is safe
+// sequences    Vec<Vec<usize>>   the safe sequences listed
+// complete     bool              whether every safe sequence was listed
+// count        Option<u128>      number of safe sequences, if counted (or listed in full, or
+//                                0 if the state is unsafe)
+// out          dyn Write         where to print the results
 //
-//**********************************************************
-fn read_request_vector(reader: &mut BufReader<File>) -> (usize, Vec<i32>) {
-
-    //initialize the line reader and skip over the blank line
-    let mut line = String::new();
-    reader.read_line(&mut line).unwrap();
-    line.clear();
-    reader.read_line(&mut line).unwrap();
-
-    //initialize the vector that stores our final result
-    //and the usize that stores the process id
-    let mut request_vec: Vec<i32> = vec![];
-    let mut process_id: usize = 0;
-
-    //parse the line into the process id and the request vector
-    for (i, part) in line.trim().split([':', ' ']).enumerate() {
-
-        //the first part is the process id
-        if i == 0 {
-            process_id = part.parse().unwrap();
-        }
-        //the rest of the parts are the request vector values
-        else {
-            request_vec.push(part.parse().unwrap());
-        }
+//*********************************************************
+fn sequences_command(args: &[String], options: &Options) -> ! {
+    let mut args = args.to_vec();
+    let count_only = take_flag(&mut args, "--count");
+    let limit = match take_option(&mut args, "--limit") {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            usage_error("sequences", &format!("'--limit' needs a non-negative integer, found '{}'", value))
+        }),
+        None => 100,
+    };
+    if let Some(arg) = args.iter().find(|arg| arg.starts_with("--")) {
+        usage_error("sequences", &format!("unknown option '{}'", arg));
     }
-    (process_id, request_vec)
+    let (state, _) = load_scenario(&input_path("sequences", &args, 1), options);
+
+    //an unsafe state has no safe sequences, so there is nothing to search for
+    let safe = state.is_safe_state();
+    let (sequences, complete) = match (safe, count_only) {
+        (false, _) => (vec![], true),
+        (true, true) => (vec![], false),
+        (true, false) => state.safe_sequences(limit),
+    };
+
+    //a complete listing is its own count; only '--count' runs the
+    //(much slower) exact count, as a listing cut short gives a lower bound
+    let count = match (safe, count_only, complete) {
+        (false, _, _) => Some(0),
+        (true, true, _) => state.count_safe_sequences(),
+        (true, false, true) => Some(sequences.len() as u128),
+        (true, false, false) => None,
+    };
+    let status = match safe {
+        true => ExitStatus::Granted,
+        false => ExitStatus::Unsafe,
+    };
+
+    let mut out = open_output(options, options.json);
+    let result = match options.json {
+        true => {
+            let mut members = vec![
+                ("processes", Json::Number(state.num_processes() as i128)),
+                ("resource_types", Json::Number(state.num_resources() as i128)),
+                ("names", names_to_json(&state)),
+                ("state", state_to_json(&state)),
+                ("safety", safety_to_json(&state.check_safety_with(&options.policy).expect("the policy was checked by 'load_scenario'"))),
+            ];
+            if !count_only {
+                members.push(("safe_sequences", Json::Array(sequences.iter().map(|sequence| Json::processes(sequence)).collect())));
+                members.push(("complete", Json::Bool(complete)));
+            }
+            members.push(("count", count.map_or(Json::Null, |count| Json::Number(count as i128))));
+            writeln!(out, "{}", Json::object(members))
+        }
+        false => print_sequences(&mut out, &state, &sequences, complete, count, count_only, options),
+    };
+    finish(out, result.map(|_| status));
 }
 
 //*********************************************************
 //
-// Safe State Check Function
+// Print Sequences Function
 //
-// Confirms whether the state indicated by the matrices
-// 'max' and 'allocation', as well as the vector 'available' 
-// is safe or not.
+// Prints the state, then either why it is unsafe, or the
+// safe sequences listed and the number of them.
 //
 // Return Value
 // ------------
-// bool      true if safe state, false if not
+// io::Result<()>   the error encountered while writing, if any
 //
 // Function Parameters
 // -------------------
-// num_processes     usize          value      number of processes
-// num_resources     usize          value      number of resource types
-// max_matrix        Vec<Vec<i32>>  reference  max matrix
-// allocation_matrix Vec<Vec<i32>>  reference  allocation matrix
-// available_vec     Vec<i32>       reference  available vector
+// out          dyn Write         reference  where to print the results
+// state        BankerState       reference  state of the system
+// sequences    Vec<Vec<usize>>   reference  the safe sequences listed
+// complete     bool              value      whether every safe sequence was listed
+// count        Option<u128>      value      number of safe sequences, if it could be counted
+// count_only   bool              value      whether the sequences were counted without listing them
+// options      Options           reference  options given on the command line (the style and policy)
 //
 // Local Variables
 // ---------------
-// work_vec          Vec<i32>       work vector, used to track available resources
-// finish_vec        Vec<bool>      finish vector, used to track which processes have finished
-// safe_sequence     Vec<i32>       safe sequence, used to track the safe sequence of processes
-// found             bool           used to track whether a process in the list that can finish has been found
-// can_finish        bool           used to track whether an individual process can actually finish
-// i                 usize          loop counter for processes
-// j                 usize          loop counter for resources
-//
-//*********************************************************
-fn is_safe_state(num_processes: usize, num_resources: usize, max_matrix: &Vec<Vec<i32>>, 
-                    allocation_matrix: &Vec<Vec<i32>>, available_vec: &Vec<i32>) -> bool {
-
-    //create work vector to keep track of available resources
-    let mut work_vec = available_vec.clone();
-
-    //create finish vector to keep track of which processes have finished
-    let mut finish_vec = vec![false; num_processes];
-
-    //create safe sequence vector to keep track of the safe sequence of processes
-    let mut safe_sequence = vec![];
-
-    //loop until all processes that can finish have finished
-    loop {
-
-        //used to track whether a process that can finish has been found
-        //among those that haven't yet finished
-        let mut found = false;
-
-        //look for a process in the list that currently can finish 
-        for i in 0..num_processes {
-
-            //if the process hasn't finished, let's check if it can finish
-            if finish_vec[i] == false {
-
-                //used to track whether the process can finish
-                let mut can_finish = true;
-
-                //check if the process requirements can be met with the available resources
-                for j in 0..num_resources {
-                    if max_matrix[i][j] - allocation_matrix[i][j] > work_vec[j] {
-                        can_finish = false;
-                        break;
-                    }
-                }
-                //if the resource requirements for the process can be met,
-                //add the process to the safe sequence, add the resources
-                //to the work vector, mark the process as finished and
-                //mark that a process has been found that can finish
-                if can_finish {
-                    found = true;
-                    finish_vec[i] = true;
-                    for j in 0..num_resources {
-                        work_vec[j] += allocation_matrix[i][j];
-                    }
-                    safe_sequence.push(i);
-                    break;
-                }
-            }
+// i          usize         loop counter for sequences
+// steps      Vec<String>   process names of the current sequence, in order
+//
+//*********************************************************
+fn print_sequences(out: &mut dyn Write, state: &BankerState, sequences: &[Vec<usize>], complete: bool,
+                   count: Option<u128>, count_only: bool, options: &Options) -> io::Result<()> {
+    writeln!(out, "There are {0} processes and {1} resource types in the system.\n", state.num_processes(), state.num_resources())?;
+    print_state(out, state, options.style)?;
+    if let Safety::Unsafe(report) = state.check_safety_with(&options.policy).expect("the policy was checked by 'load_scenario'") {
+        writeln!(out, "THE SYSTEM IS NOT IN A SAFE STATE.")?;
+        return print_unsafe_report(out, &report, state.names(), options.style);
+    }
+    writeln!(out, "THE SYSTEM IS IN A SAFE STATE.")?;
+
+    if !count_only {
+        match complete {
+            true => writeln!(out, "Safe sequences:")?,
+            false => writeln!(out, "Safe sequences (the first {}):", sequences.len())?,
         }
-        //break out of the loop once no process has been found
-        //that can finish
-        if !found {
-            break;
+        for (i, sequence) in sequences.iter().enumerate() {
+            let steps: Vec<String> = sequence.iter().map(|process| state.names().process(*process)).collect();
+            writeln!(out, "{:>4}. {}", i + 1, steps.join(" -> "))?;
         }
     }
-    //if the safe sequence contains all of the processes, we are in a safe state
-    if safe_sequence.len() == num_processes {
-        return true;
-    }
-    //otherwise, we are not in a safe state
-    else{
-        return false;
+    match (count, count_only) {
+        (Some(count), _) => writeln!(out, "Number of safe sequences: {}\n", count),
+        (None, false) => writeln!(out, "Number of safe sequences: at least {} (use --count for the exact number)\n", sequences.len()),
+        (None, true) => writeln!(out, "Number of safe sequences: too many processes to count (more than {})\n", MAX_COUNTED_PROCESSES),
     }
-} 
+}
 
 //*********************************************************
 //
-// Banker's Algorithm Function
+// Convert Command Function
 //
-// Runs the Banker's Algorithm on the given input file using
-// the helper functions implemented above.
+// Runs the 'convert' subcommand: writes the state and the
+// events in the input file in the other format, or in the
+// format given with '--to'.
 //
-// Return Values
+// Return Value
 // ------------
-// none
+// none (the program ends with the code of an ExitStatus)
 //
 // Function Parameters
 // -------------------
-// reader            BufReader<File>  reference  file reader
-// num_processes     usize            value      number of processes
-// num_resources     usize            value      number of resources
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
 //
 // Local Variables
 // ---------------
-// resource_labels   Vec<String>     resource labels ('A', 'B', 'C', etc.)
-// resource_vec      Vec<i32>        resource vector
-// available_vec     Vec<i32>        available vector
-// max_matrix        Vec<Vec<i32>>   max matrix
-// allocation_matrix Vec<Vec<i32>>   allocation matrix
-// need_matrix       Vec<Vec<i32>>   need matrix
-// request_vec       Vec<i32>        request vector
-// process_id        usize           process ID of the requesting process
-// is_safe           bool            used to determine if a given or resulting state is safe
-// is_valid          bool            used to determine if a request can be granted with the available resources
+// path      String        path of the input file, or '-' for stdin
+// state     BankerState   state of the system read from the file
+// events    Vec<Event>    requests and releases read from the file
+// to_json   bool          whether to write JSON
+// out       dyn Write     where to write the converted input
 //
-//**********************************************************
-fn run_banker(reader: &mut BufReader<File>, num_processes: usize, num_resources: usize) {
+//*********************************************************
+fn convert_command(args: &[String], options: &Options) -> ! {
+    let path = input_path("convert", args, 1);
+    let (state, events) = load_scenario(&path, options);
+
+    //without '--to', convert to the format the input is not in
+    let to_json = match options.to.as_deref() {
+        Some(format) => format == "json",
+        None => match options.input_format.as_deref() {
+            Some(format) => format == "text",
+            None => !is_json_path(&path),
+        },
+    };
+
+    let mut out = open_output(options, true);
+    let result = match to_json {
+        true => writeln!(out, "{}", input_to_json(&state, &events)),
+        false => write_input(&mut out, &state, &events),
+    };
+    finish(out, result.map(|_| ExitStatus::Granted));
+}
 
-    //print number of processes and resource types
-    eprintln!("There are {0} processes and {1} resource types in the system.\n", num_processes, num_resources);
-
-    //read and print resource labels and vector
-    let (resource_labels, resource_vec) = read_resource_vector(reader);
-    print_vector(&resource_vec, "Resource", &resource_labels, 0);
- 
-    //read and print available vector
-    let mut available_vec = read_available_vector(reader);
-    print_vector(&available_vec, "Available", &resource_labels, 0);
- 
-    //read and print max matrix
-    let max_matrix = read_matrix(reader, num_processes);
-    print_matrix(&max_matrix, "Max", &resource_labels);
- 
-    //read and print allocation matrix
-    let mut allocation_matrix = read_matrix(reader, num_processes);
-    print_matrix(&allocation_matrix, "Allocation", &resource_labels);
- 
-    //compute and print need matrix
-    let mut need_matrix = compute_need_matrix(num_processes, num_resources, &max_matrix, &allocation_matrix);
-    print_matrix(&need_matrix, "Need", &resource_labels);
- 
-    //determine if the current system is in safe state
-    let is_safe = is_safe_state(num_processes, num_resources, &max_matrix, &allocation_matrix, &available_vec);
- 
-    //if the system is in a safe state, read the request vector and process id,
-    //and determine if the request can be fulfilled
-    if is_safe == true {
- 
-        eprintln!("THE SYSTEM IS IN A SAFE STATE.\n");
- 
-        //read and print the request vector
-        let (process_id, request_vec) = read_request_vector(reader);
-        print_vector(&request_vec, "Request", &resource_labels, process_id);
- 
-        //check if request is valid by simulating fulfillment of the request
-        let is_valid = fulfill_request(process_id, &request_vec, num_resources, &mut available_vec, &mut allocation_matrix, &mut need_matrix);
- 
-        //if the request is valid, determine if the resulting state is safe
-        if is_valid == true {
-
-            let is_safe = is_safe_state(num_processes, num_resources, &max_matrix, &allocation_matrix, &available_vec);
- 
-            //if the resulting state is safe, print the new state
-            if is_safe == true {
-                eprintln!("THE REQUEST CAN BE GRANTED: NEW STATE FOLLOWS\n");
-                print_vector(&resource_vec, "Resource", &resource_labels, 0);
-                print_vector(&available_vec, "Available", &resource_labels, 0);
-                print_matrix(&max_matrix, "Max", &resource_labels);
-                print_matrix(&allocation_matrix, "Allocation", &resource_labels);
-                print_matrix(&need_matrix, "Need", &resource_labels);
-            }
-            //if the resulting state is not safe, indicate so
-            else {
-                eprintln!("THE REQUEST CANNOT BE GRANTED.");
-            } 
-        }
-        //if the request is not valid, indicate so
-        else{
-            eprintln!("THE REQUEST CANNOT BE GRANTED.");
-        }  
+//*********************************************************
+//
+// Generate Command Function
+//
+// Runs the 'generate' subcommand: writes a random safe
+// state in the text format, or in the format given with
+// '--to'.
+//
+// Return Value
+// ------------
+// none (the program ends with the code of an ExitStatus)
+//
+// Function Parameters
+// -------------------
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
+//
+// Local Variables
+// ---------------
+// count           closure       reads a count option, with its default
+// num_processes   usize         number of processes to generate
+// num_resources   usize         number of resource types to generate
+// seed            u64           seed of the random numbers (from the clock if not given)
+// state           BankerState   the generated state
+// out             dyn Write     where to write the state
+//
+//*********************************************************
+fn generate_command(args: &[String], options: &Options) -> ! {
+    let mut args = args.to_vec();
+    let mut number = |option: &str, default: u64| match take_option(&mut args, option) {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            usage_error("generate", &format!("'{}' needs a non-negative integer, found '{}'", option, value))
+        }),
+        None => default,
+    };
+    let num_processes = number("--processes", 5) as usize;
+    let num_resources = number("--resources", 3) as usize;
+    let clock = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+    let seed = number("--seed", clock);
+    if let Some(arg) = args.first() {
+        usage_error("generate", &format!("unexpected argument '{}'", arg));
+    }
+
+    let state = generate_state(num_processes, num_resources, seed);
+    let mut out = open_output(options, true);
+    let result = match options.to.as_deref() {
+        Some("json") => writeln!(out, "{}", input_to_json(&state, &[])),
+        _ => write_input(&mut out, &state, &[]),
+    };
+    finish(out, result.map(|_| ExitStatus::Granted));
+}
+
+//*********************************************************
+//
+// Repl Command Function
+//
+// Runs the 'repl' subcommand: loads the state in the input
+// file and runs the commands read from stdin on it,
+// printing a prompt if stdin is a terminal.
+//
+// Return Value
+// ------------
+// none (the program ends with the code of an ExitStatus)
+//
+// Function Parameters
+// -------------------
+// args      Vec<String>   reference  arguments of the subcommand
+// options   Options       reference  options given on the command line
+//
+// Local Variables
+// ---------------
+// state    BankerState   state of the system read from the file
+// repl     Repl          the interactive session
+// stdin    Stdin         where the commands are read from
+// prompt   Option<&str>  prompt printed before each command, if stdin is a terminal
+//
+//*********************************************************
+fn repl_command(args: &[String], options: &Options) -> ! {
+    if options.json || options.output.is_some() {
+        usage_error("repl", "the session is always printed as text to stdout");
     }
-    //if the system is not in a safe state, indicate so
-    else{
-        eprintln!("THE SYSTEM IS NOT IN A SAFE STATE.");
+    if args.len() != 1 || args[0] == "-" {
+        usage_error("repl", "expected an input file (stdin is where the commands are read from)");
     }
+    let (state, _) = load_scenario(&args[0], options);
+
+    let mut repl = Repl::new(state, options.style).with_policy(options.policy.clone()).expect("the policy was checked by 'load_scenario'");
+    let stdin = io::stdin();
+    let prompt = stdin.is_terminal().then_some("banker> ");
+    let result = run_repl(&mut repl, &mut stdin.lock(), &mut io::stdout(), prompt);
+    finish(Box::new(io::stdout()), result.map(|_| ExitStatus::Granted));
 }
 
 //*********************************************************
 //
 // Main Function
 //
-// Opens the file specified by the command line argument, 
-// verifies that a valid number of resource types and 
-// processes are entered on the first line, and
-// calls the Banker's Algorithm function to operate on the
-// remainder of the file. 
+// Runs the subcommand given on the command line (or
+// 'simulate' if none is given, so 'banker <input file>'
+// works as it always has). The input is read from a file,
+// or from stdin if the file is '-' or missing; files
+// ending in '.json' (or any input, with '--input-format
+// json') are read as JSON. Errors in the input are printed
+// as diagnostics and end the program with an error code.
+// With '--lenient', inconsistencies in the state are
+// printed as warnings instead. With '--borders' the
+// vectors and matrices are boxed in, and with '--trace'
+// every safety check is shown step by step as a
+// Work/Finish table. '--policy' picks which process the
+// safety algorithm lets finish next when several can, so
+// the safe sequence is a recommended execution order.
+// With '--format json', the results
+// are written to stdout as one JSON document.
+// The results go to stderr (stdout for JSON) unless
+// '--output <file>' names a file for them; diagnostics
+// always go to stderr.
 //
 // Return Value
 // ------------
-// i32                exit code (0 if successful, 1 if not)
+// none (the program ends with the code of an ExitStatus)
 //
 // Function Parameters
 // -------------------
@@ -712,48 +1293,54 @@ fn run_banker(reader: &mut BufReader<File>, num_processes: usize, num_resources:
 //
 // Local Variables
 // ---------------
-// args               Vec<String>     command line arguments
-// file               File            input file
-// reader             BufReader<File> file reader
-// num_processes      usize           number of processes
-// num_resources      usize           number of resources
+// args      Vec<String>   command line arguments
+// options   Options       options given on the command line
+// command   String        subcommand to run
 //
 //*********************************************************
 fn main() {
 
-    //collect arguments from command line
-    let args: Vec<String> = env::args().collect();
-
-    //check for correct usage/number of arguments
-    if args.len() != 2{
-        eprintln!("Usage: ./banker <input file> OR cargo run -- <input file>");
-        process::exit(1);
-    }
-
-    //check that a valid input filepath was entered in the arguments
-    if Path::new(&args[1]).exists() == false {
-        eprintln!("Error: File does not exist");
-        process::exit(1);
+    //collect arguments from command line, and the options shared by every subcommand
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_options(&mut args);
+    let command = match args.first().map(String::as_str) {
+        Some("check" | "detect" | "request" | "release" | "simulate" | "sequences" | "convert" | "generate" | "repl" | "help") => args.remove(0),
+        _ if options.help => "help".to_string(),
+        _ => "simulate".to_string(),
+    };
+
+    //any option left over is not one the program knows, unless the subcommand has its own
+    if let Some(arg) = args.iter().find(|arg| arg.starts_with("--")) {
+        if !matches!(command.as_str(), "generate" | "sequences") {
+            usage_error(&command, &format!("unknown option '{}'", arg));
+        }
     }
 
-    //open input file and the file reader
-    let file = File::open(&args[1]).unwrap();
-    let mut reader = BufReader::new(file);
-    
-    //read first line (number of processes and resource types)
-    let (num_processes, num_resources) = read_first_line(&mut reader);
-
-    //check that the number of processes and resources is valid
-    if num_processes > 1024{
-        eprintln!("Error: Number of processes must be less than or equal to 1024");
-        process::exit(1);
+    //'help <command>' is the same as '<command> --help'
+    if command == "help" {
+        print_help(args.first().map_or("", String::as_str));
+        ExitStatus::Granted.exit();
     }
-    if num_resources > 26{
-        eprintln!("Error: Number of resource types must be less than or equal to 26");
-        process::exit(1);
+    if options.help {
+        print_help(&command);
+        ExitStatus::Granted.exit();
     }
 
-    //run banker's algorithm and exit program on completion
-    run_banker(&mut reader, num_processes, num_resources);
-    process::exit(0);
-}
\ No newline at end of file
+    match command.as_str() {
+        "check" => {
+            let (state, _) = load_scenario(&input_path("check", &args, 1), &options);
+            run(state, &[], &options);
+        }
+        "detect" => detect_command(&args, &options),
+        "request" => event_command(EventKind::Request, &args, &options),
+        "release" => event_command(EventKind::Release, &args, &options),
+        "convert" => convert_command(&args, &options),
+        "sequences" => sequences_command(&args, &options),
+        "generate" => generate_command(&args, &options),
+        "repl" => repl_command(&args, &options),
+        _ => {
+            let (state, events) = load_scenario(&input_path("simulate", &args, 1), &options);
+            run(state, &events, &options);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//*********************************************************
+//
+// Deadlock Detection
+//
+// The deadlock detection algorithm (Coffman; Shoshani):
+// unlike the Banker's Algorithm, which avoids unsafe
+// states using the maximum claims, it takes what each
+// process is blocked waiting for right now (the Request
+// matrix) and reports the processes that are deadlocked.
+//
+//*********************************************************
+use crate::state::ShapeError;
+
+//*********************************************************
+//
+// Detection Struct
+//
+// Result of running the detection algorithm.
+//
+// Fields
+// ------
+// finished     Vec<usize>   processes that could finish, in the order found (processes
+//                           holding no resources first, as they cannot be deadlocked)
+// deadlocked   Vec<usize>   processes that are deadlocked
+// work         Vec<i32>     the Work vector once no more processes could finish
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    pub finished: Vec<usize>,
+    pub deadlocked: Vec<usize>,
+    pub work: Vec<i32>,
+}
+
+impl Detection {
+
+    //true if some process is deadlocked
+    pub fn is_deadlocked(&self) -> bool {
+        !self.deadlocked.is_empty()
+    }
+}
+
+//*********************************************************
+//
+// Detect Deadlock Function
+//
+// Runs the detection algorithm. A process holding no
+// resources cannot be part of a deadlock. Otherwise a
+// process whose outstanding request can be met by the
+// Work vector is assumed to get it, finish, and return
+// its allocation to the Work vector; the processes left
+// once no such process remains are deadlocked. Both
+// matrices must have one row per process, and every row
+// one column per resource type.
+//
+// Return Value
+// ------------
+// Result<Detection, ShapeError>   the processes that could finish and those that are
+//                                 deadlocked, or the first part of a matrix with the wrong size
+//
+// Function Parameters
+// -------------------
+// available_vec       Vec<i32>        reference  instances of each resource type not allocated
+// allocation_matrix   Vec<Vec<i32>>   reference  resources allocated to each process
+// request_matrix      Vec<Vec<i32>>   reference  resources each process is blocked waiting for
+//
+// Local Variables
+// ---------------
+// name         &str         name of the matrix being checked
+// matrix       Vec<Vec<i32>>  matrix being checked
+// work_vec     Vec<i32>     work vector, used to track available resources
+// finish_vec   Vec<bool>    finish vector, used to track which processes have finished
+// finished     Vec<usize>   processes that have finished, in order
+// found        bool         used to track whether a process that can finish has been found
+// i            usize        loop counter for processes
+// deadlocked   Vec<usize>   processes that could not finish
+//
+//*********************************************************
+pub fn detect_deadlock(available_vec: &[i32], allocation_matrix: &[Vec<i32>], request_matrix: &[Vec<i32>])
+                       -> Result<Detection, ShapeError> {
+
+    //both matrices must match the processes and resource types
+    if request_matrix.len() != allocation_matrix.len() {
+        return Err(ShapeError { context: "the rows of the Request matrix".to_string(), expected: allocation_matrix.len(),
+                                found: request_matrix.len() });
+    }
+    for (name, matrix) in [("Allocation", allocation_matrix), ("Request", request_matrix)] {
+        if let Some((i, row)) = matrix.iter().enumerate().find(|(_, row)| row.len() != available_vec.len()) {
+            return Err(ShapeError { context: format!("row {} of the {} matrix", i + 1, name), expected: available_vec.len(),
+                                    found: row.len() });
+        }
+    }
+
+    let mut work_vec = available_vec.to_vec();
+
+    //processes holding nothing cannot be deadlocked, so they start out finished
+    let mut finish_vec: Vec<bool> = allocation_matrix.iter().map(|row| row.iter().all(|value| *value == 0)).collect();
+    let mut finished: Vec<usize> = (0..finish_vec.len()).filter(|i| finish_vec[*i]).collect();
+
+    //let any process whose request can be met finish and release its allocation,
+    //until no such process is left
+    loop {
+        let mut found = false;
+        for i in 0..finish_vec.len() {
+            if !finish_vec[i] && request_matrix[i].iter().zip(&work_vec).all(|(request, work)| request <= work) {
+                for (work, allocation) in work_vec.iter_mut().zip(&allocation_matrix[i]) {
+                    *work += allocation;
+                }
+                finish_vec[i] = true;
+                finished.push(i);
+                found = true;
+            }
+        }
+        if !found {
+            break;
+        }
+    }
+
+    let deadlocked = (0..finish_vec.len()).filter(|i| !finish_vec[*i]).collect();
+    Ok(Detection { finished, deadlocked, work: work_vec })
+}
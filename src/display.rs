@@ -0,0 +1,567 @@
+//*********************************************************
+//
+// Display Functions
+//
+// Prints vectors and matrices of the Banker's Algorithm
+// to any writer (stderr, stdout or a file), labeled by
+// resource type and process, along with diagnostics for
+// errors in the input file, which always go to stderr.
+//
+//*********************************************************
+use std::io::{self, Write};
+
+use crate::detect::Detection;
+use crate::error::ParseError;
+use crate::names::Names;
+use crate::state::{BankerState, TraceStep, UnsafeReport};
+
+//width of a printed value or label
+fn text_width(text: &str) -> usize {
+    text.chars().count()
+}
+
+//*********************************************************
+//
+// TableStyle Enum
+//
+// How a table of values is drawn.
+//
+// Variants
+// --------
+// Plain      columns separated by spaces, rows labeled "P: "
+// Bordered   every cell boxed in with '+', '-' and '|'
+//
+//*********************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableStyle {
+    #[default]
+    Plain,
+    Bordered,
+}
+
+//*********************************************************
+//
+// Table Struct
+//
+// A vector or matrix laid out for printing. Every column is
+// as wide as the widest of its label and its values, with
+// the values right-aligned, and the process labels are
+// right-aligned in a gutter as wide as the widest of them.
+//
+// Fields
+// ------
+// labels       Vec<String>           resource label of each column
+// row_labels   Option<Vec<String>>   process label of each row (None for an unlabeled vector)
+// rows         Vec<Vec<i32>>         values of each row
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub labels: Vec<String>,
+    pub row_labels: Option<Vec<String>>,
+    pub rows: Vec<Vec<i32>>,
+}
+
+impl Table {
+
+    //width of each column: the widest of its label and its values
+    pub fn column_widths(&self) -> Vec<usize> {
+        self.labels.iter().enumerate()
+            .map(|(j, label)| {
+                self.rows.iter()
+                    .filter_map(|row| row.get(j))
+                    .map(|value| text_width(&value.to_string()))
+                    .fold(text_width(label), usize::max)
+            })
+            .collect()
+    }
+
+    //width of the process gutter (None if the rows are unlabeled)
+    pub fn gutter_width(&self) -> Option<usize> {
+        self.row_labels.as_ref().map(|row_labels| row_labels.iter().map(|label| text_width(label)).max().unwrap_or(0))
+    }
+
+    //*********************************************************
+    //
+    // Render Function
+    //
+    // Lays out the table in the given style, one line per
+    // row after a line of resource labels.
+    //
+    // Return Value
+    // ------------
+    // String   the table, with every line ending in a newline
+    //
+    // Function Parameters
+    // -------------------
+    // style   TableStyle   value   plain or bordered
+    //
+    // Local Variables
+    // ---------------
+    // widths   Vec<usize>      width of each column
+    // gutter   Option<usize>   width of the process gutter
+    // out      String          the table so far
+    // i        usize           loop counter for rows
+    // row      Vec<i32>        current row
+    // label    String          label of the current row
+    //
+    //*********************************************************
+    pub fn render(&self, style: TableStyle) -> String {
+        let widths = self.column_widths();
+        let gutter = self.gutter_width();
+        let mut out = String::new();
+
+        match style {
+            TableStyle::Plain => {
+                if let Some(gutter) = gutter {
+                    out.push_str(&" ".repeat(gutter + 2));
+                }
+                for (label, width) in self.labels.iter().zip(&widths) {
+                    out.push_str(&format!("{:>width$} ", label, width = width));
+                }
+                out.push('\n');
+
+                for (i, row) in self.rows.iter().enumerate() {
+                    if let (Some(gutter), Some(row_labels)) = (gutter, &self.row_labels) {
+                        out.push_str(&format!("{:>gutter$}: ", row_labels[i], gutter = gutter));
+                    }
+                    for (value, width) in row.iter().zip(&widths) {
+                        out.push_str(&format!("{:>width$} ", value, width = width));
+                    }
+                    out.push('\n');
+                }
+            }
+            TableStyle::Bordered => {
+
+                //the gutter is just another column, with a blank label
+                let rule: String = gutter.iter().chain(&widths)
+                    .map(|width| format!("+{}", "-".repeat(width + 2)))
+                    .collect::<String>() + "+\n";
+
+                out.push_str(&rule);
+                if let Some(gutter) = gutter {
+                    out.push_str(&format!("| {} ", " ".repeat(gutter)));
+                }
+                for (label, width) in self.labels.iter().zip(&widths) {
+                    out.push_str(&format!("| {:>width$} ", label, width = width));
+                }
+                out.push_str("|\n");
+                out.push_str(&rule);
+
+                for (i, row) in self.rows.iter().enumerate() {
+                    if let (Some(gutter), Some(row_labels)) = (gutter, &self.row_labels) {
+                        out.push_str(&format!("| {:>gutter$} ", row_labels[i], gutter = gutter));
+                    }
+                    for (value, width) in row.iter().zip(&widths) {
+                        out.push_str(&format!("| {:>width$} ", value, width = width));
+                    }
+                    out.push_str("|\n");
+                }
+                out.push_str(&rule);
+            }
+        }
+        out
+    }
+}
+
+//*********************************************************
+//
+// Print Vector Function
+//
+// Prints the type of vector and the vector itself as a
+// table, with values labeled by the provided resource
+// labels.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out         dyn Write      reference  where to print the vector
+// vector      Vec<i32>       reference  vector to print
+// name        &str           reference  type of vector (e.g. "Request", "Available", etc.)
+// labels      Vec<String>    reference  resource labels for vector (e.g. ["A", "B", "C"])
+// process     Option<&str>   value      process label for request and release vectors (None for other vectors)
+// style       TableStyle     value      plain or bordered
+//
+// Local Variables
+// ---------------
+// table   Table   the vector laid out as a one-row table
+//
+//*********************************************************
+pub fn print_vector(out: &mut dyn Write, vector: &[i32], name: &str, labels: &[String], process: Option<&str>,
+                    style: TableStyle) -> io::Result<()> {
+    let table = Table {
+        labels: labels.to_vec(),
+        row_labels: process.map(|process| vec![process.to_string()]),
+        rows: vec![vector.to_vec()],
+    };
+
+    //print the header message specifying the vector type, then the vector
+    writeln!(out, "The {} Vector is: ", name)?;
+    writeln!(out, "{}", table.render(style))
+}
+
+//*********************************************************
+//
+// Print Matrix Function
+//
+// Prints the type of matrix and the matrix itself as a
+// table, with values labeled by the resource labels and
+// rows by the process labels.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out          dyn Write      reference  where to print the matrix
+// matrix       Vec<Vec<i32>>  reference  matrix to print
+// name         &str           reference  type of matrix (e.g. "Max", "Allocation", etc.)
+// labels       Vec<String>    reference  resource labels for matrix (e.g. ["A", "B", "C"])
+// row_labels   Vec<String>    reference  process labels for matrix rows (e.g. ["0", "1", "2"])
+// style        TableStyle     value      plain or bordered
+//
+// Local Variables
+// ---------------
+// table   Table   the matrix laid out as a table
+//
+//*********************************************************
+pub fn print_matrix(out: &mut dyn Write, matrix: &[Vec<i32>], name: &str, labels: &[String], row_labels: &[String],
+                    style: TableStyle) -> io::Result<()> {
+    let table = Table {
+        labels: labels.to_vec(),
+        row_labels: Some(row_labels.to_vec()),
+        rows: matrix.to_vec(),
+    };
+
+    //print the header message specifying the matrix type, then the matrix
+    writeln!(out, "The {} Matrix is: ", name)?;
+    writeln!(out, "{}", table.render(style))
+}
+
+//*********************************************************
+//
+// Print State Function
+//
+// Prints the resource and available vectors, and the max,
+// allocation and need matrices of a state, labeled by the
+// names of its resource types and processes.
+//
+// Return Value
+// ------------
+// io::Result<()>    the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out               dyn Write        reference  where to print the state
+// state             BankerState      reference  state to print
+// style             TableStyle       value      plain or bordered tables
+//
+// Local Variables
+// ---------------
+// resource_labels   Vec<String>      resource labels ("A", "B", "C", etc., or their names)
+// process_labels    Vec<String>      process labels ("0", "1", "2", etc., or their names)
+//
+//*********************************************************
+pub fn print_state(out: &mut dyn Write, state: &BankerState, style: TableStyle) -> io::Result<()> {
+    let resource_labels = state.names().resource_labels(state.num_resources());
+    let process_labels = state.names().process_rows(state.num_processes());
+    print_vector(out, state.resource_vec(), "Resource", &resource_labels, None, style)?;
+    print_vector(out, state.available_vec(), "Available", &resource_labels, None, style)?;
+    print_matrix(out, state.max_matrix(), "Max", &resource_labels, &process_labels, style)?;
+    print_matrix(out, state.allocation_matrix(), "Allocation", &resource_labels, &process_labels, style)?;
+    print_matrix(out, state.need_matrix(), "Need", &resource_labels, &process_labels, style)
+}
+
+//*********************************************************
+//
+// Print Safe Sequence Function
+//
+// Prints a safe sequence of processes in the form
+// "Safe sequence: P1 -> P3 -> P0", using the process names
+// if the processes are named.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out        dyn Write    reference  where to print the sequence
+// sequence   Vec<usize>   reference  safe sequence of process IDs
+// names      Names        reference  names of the processes
+//
+// Local Variables
+// ---------------
+// steps   Vec<String>   process labels in sequence order
+//
+//*********************************************************
+pub fn print_safe_sequence(out: &mut dyn Write, sequence: &[usize], names: &Names) -> io::Result<()> {
+    let steps: Vec<String> = sequence.iter().map(|process_id| names.process(*process_id)).collect();
+    writeln!(out, "Safe sequence: {}\n", steps.join(" -> "))
+}
+
+//*********************************************************
+//
+// Print Unsafe Report Function
+//
+// Explains why a state is not safe: the processes that
+// could finish before the safety algorithm got stuck, the
+// Work vector it got stuck with, what each blocked process
+// is short of, and the bottleneck resources that hold the
+// blocked processes back.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out      dyn Write      reference  where to print the report
+// report   UnsafeReport   reference  why the state is not safe
+// names    Names          reference  names of the resource types and processes
+// style    TableStyle     value      plain or bordered
+//
+// Local Variables
+// ---------------
+// finished    Vec<String>       processes that could finish, in order
+// process     usize             current blocked process
+// deficit     Vec<i64>          its deficit of each resource type
+// short       Vec<String>       the resource types it is short of, and by how much
+// summary     ResourceDeficit   current bottleneck resource
+// processes   Vec<String>       blocked processes short of it
+//
+//*********************************************************
+pub fn print_unsafe_report(out: &mut dyn Write, report: &UnsafeReport, names: &Names, style: TableStyle) -> io::Result<()> {
+    let finished: Vec<String> = report.finished.iter().map(|process| names.process(*process)).collect();
+    match finished.is_empty() {
+        true => writeln!(out, "No process can finish.\n")?,
+        false => writeln!(out, "Finished before the system got stuck: {}\n", finished.join(" -> "))?,
+    }
+    print_vector(out, &report.work, "Final Work", &names.resource_labels(report.work.len()), None, style)?;
+
+    writeln!(out, "Blocked processes:")?;
+    for (process, deficit) in report.blocked.iter().zip(&report.deficits) {
+        let short: Vec<String> = deficit.iter().enumerate()
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(j, amount)| format!("{} more of {}", amount, names.resource(j)))
+            .collect();
+        writeln!(out, "  {}: needs {}", names.process(*process), short.join(", "))?;
+    }
+
+    writeln!(out, "Bottleneck resources:")?;
+    for summary in report.resource_deficits() {
+        let processes: Vec<String> = summary.processes.iter().map(|process| names.process(*process)).collect();
+        writeln!(out, "  {}: short by {} in total ({} at most), blocking {}",
+                 names.resource(summary.resource), summary.total, summary.largest, processes.join(", "))?;
+    }
+    writeln!(out)
+}
+
+//*********************************************************
+//
+// Print Detection Function
+//
+// Prints the verdict of the deadlock detection algorithm:
+// the order in which the processes that are not
+// deadlocked could finish and, if some are deadlocked,
+// which ones, the Work vector the algorithm got stuck
+// with, and how far each deadlocked process's request
+// exceeds it.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out              dyn Write       reference  where to print the verdict
+// detection        Detection       reference  result of the detection algorithm
+// request_matrix   Vec<Vec<i32>>   reference  request matrix the algorithm was run on
+// names            Names           reference  names of the resource types and processes
+// style            TableStyle      value      plain or bordered
+//
+// Local Variables
+// ---------------
+// finished     Vec<String>   processes that could finish, in order
+// deadlocked   Vec<String>   processes that are deadlocked
+// process      usize         current deadlocked process
+// short        Vec<String>   the resource types it waits for, and how many more it needs (in
+//                            64 bits, as the Work vector can be negative in lenient mode)
+//
+//*********************************************************
+pub fn print_detection(out: &mut dyn Write, detection: &Detection, request_matrix: &[Vec<i32>], names: &Names,
+                       style: TableStyle) -> io::Result<()> {
+    let finished: Vec<String> = detection.finished.iter().map(|process| names.process(*process)).collect();
+    if !detection.is_deadlocked() {
+        writeln!(out, "NO PROCESS IS DEADLOCKED.")?;
+        return writeln!(out, "Completion order: {}\n", finished.join(" -> "));
+    }
+
+    let deadlocked: Vec<String> = detection.deadlocked.iter().map(|process| names.process(*process)).collect();
+    writeln!(out, "THE SYSTEM IS DEADLOCKED.")?;
+    writeln!(out, "Deadlocked processes: {}", deadlocked.join(", "))?;
+    if !finished.is_empty() {
+        writeln!(out, "Could finish: {}", finished.join(" -> "))?;
+    }
+    writeln!(out)?;
+    print_vector(out, &detection.work, "Final Work", &names.resource_labels(detection.work.len()), None, style)?;
+
+    writeln!(out, "Waiting:")?;
+    for process in &detection.deadlocked {
+        let short: Vec<String> = request_matrix[*process].iter().zip(&detection.work).enumerate()
+            .map(|(j, (request, work))| (j, (*request as i64 - *work as i64).max(0)))
+            .filter(|(_, more)| *more > 0)
+            .map(|(j, more)| format!("{} more of {}", more, names.resource(j)))
+            .collect();
+        writeln!(out, "  {}: needs {}", names.process(*process), short.join(", "))?;
+    }
+    writeln!(out)
+}
+
+//*********************************************************
+//
+// Print Trace Function
+//
+// Prints the steps of the safety algorithm as a table, one
+// row per process examined: the process, its need, the
+// work vector its need was compared against, whether it
+// could finish, and the work vector once it finished and
+// released its allocation. Vectors list their values in
+// the order of the resource labels given in the header.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out      dyn Write        reference  where to print the trace
+// trace    Vec<TraceStep>   reference  steps of the safety algorithm
+// names    Names            reference  names of the resource types and processes
+// style    TableStyle       value      plain or bordered
+//
+// Local Variables
+// ---------------
+// vector    closure            a vector as the text of a cell
+// resources String             the resource labels, in order
+// headers   Vec<String>        the header of each column
+// rows      Vec<Vec<String>>   the cells of each row
+// widths    Vec<usize>         width of each column
+// line      closure            a row of cells as a line of the table
+// rule      String             a horizontal border, for bordered tables
+//
+//*********************************************************
+pub fn print_trace(out: &mut dyn Write, trace: &[TraceStep], names: &Names, style: TableStyle) -> io::Result<()> {
+    let vector = |values: &[i32]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(" ");
+    let resources = names.resource_labels(trace.first().map_or(0, |step| step.need.len())).join(" ");
+    let headers: Vec<String> = vec![
+        "Step".to_string(),
+        "Pass".to_string(),
+        "Process".to_string(),
+        format!("Need ({})", resources),
+        format!("Work ({})", resources),
+        "Need <= Work".to_string(),
+        format!("New Work ({})", resources),
+    ];
+    let rows: Vec<Vec<String>> = trace.iter().enumerate()
+        .map(|(i, step)| vec![
+            (i + 1).to_string(),
+            step.pass.to_string(),
+            names.process(step.process_id),
+            vector(&step.need),
+            vector(&step.work),
+            if step.can_finish { "yes" } else { "no" }.to_string(),
+            step.new_work.as_deref().map_or("-".to_string(), vector),
+        ])
+        .collect();
+
+    //every column is as wide as the widest of its header and its cells
+    let widths: Vec<usize> = headers.iter().enumerate()
+        .map(|(j, header)| rows.iter().map(|row| text_width(&row[j])).fold(text_width(header), usize::max))
+        .collect();
+    let line = |cells: &[String]| -> String {
+        let cells: Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        match style {
+            TableStyle::Plain => cells.join("  ").trim_end().to_string(),
+            TableStyle::Bordered => format!("| {} |", cells.join(" | ")),
+        }
+    };
+    let rule: String = widths.iter().map(|width| format!("+{}", "-".repeat(width + 2))).collect::<String>() + "+";
+
+    writeln!(out, "The Safety Algorithm Trace is: ")?;
+    if style == TableStyle::Bordered {
+        writeln!(out, "{}", rule)?;
+    }
+    writeln!(out, "{}", line(&headers))?;
+    if style == TableStyle::Bordered {
+        writeln!(out, "{}", rule)?;
+    }
+    for row in &rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    if style == TableStyle::Bordered {
+        writeln!(out, "{}", rule)?;
+    }
+    writeln!(out)
+}
+
+//blank space up to 'column' of 'text', keeping its tabs so the caret lines up
+fn caret_indent(text: &str, column: usize) -> String {
+    text.chars().chain(std::iter::repeat(' '))
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+//*********************************************************
+//
+// Write Parse Error Function
+//
+// Writes an error found in the input file in the style of
+// a compiler diagnostic: the message, the position, and
+// the offending line with the column marked.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, if any
+//
+// Function Parameters
+// -------------------
+// out     dyn Write    reference  where to write the diagnostic
+// error   ParseError   reference  error to write
+//
+// Local Variables
+// ---------------
+// position   Position   position of the error in the file
+// gutter     String     blank space as wide as the line number
+//
+//*********************************************************
+pub fn write_parse_error(out: &mut dyn Write, error: &ParseError) -> io::Result<()> {
+    writeln!(out, "error: {}", error.message())?;
+
+    //errors that are not tied to a line only print the file name
+    let position = match error.position() {
+        Some(position) => position,
+        None => {
+            if let ParseError::Io { file, .. } = error {
+                writeln!(out, " --> {}", file)?;
+            }
+            return Ok(());
+        }
+    };
+
+    let gutter = " ".repeat(position.line.to_string().len());
+    writeln!(out, "{}--> {}:{}:{}", gutter, position.file, position.line, position.column)?;
+    writeln!(out, "{} |", gutter)?;
+    writeln!(out, "{} | {}", position.line, position.text)?;
+    writeln!(out, "{} | {}^", gutter, caret_indent(&position.text, position.column))
+}
+
+//prints an error found in the input file to stderr, as 'write_parse_error' writes it
+pub fn print_parse_error(error: &ParseError) {
+    let _ = write_parse_error(&mut io::stderr(), error);
+}
@@ -0,0 +1,112 @@
+//*********************************************************
+//
+// Error Types
+//
+// Defines the errors reported while reading an input
+// file, along with the position in the file where each
+// error was found.
+//
+//*********************************************************
+use std::error::Error;
+use std::fmt;
+
+//*********************************************************
+//
+// Position Struct
+//
+// A location in an input file.
+//
+// Fields
+// ------
+// file     String   name of the input file
+// line     usize    line number (starting at 1)
+// column   usize    column number (starting at 1)
+// text     String   full text of the line, without the line ending
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+//*********************************************************
+//
+// ParseError Enum
+//
+// An error found while reading an input file.
+//
+// Variants
+// --------
+// Io              the file could not be read
+// UnexpectedEof   the file ended before 'expected' was read
+// InvalidValue    'token' could not be read as 'expected'
+// WrongCount      a line held 'found' items (integers or names) instead of 'expected' items
+// WrongRowCount   a matrix held 'found' rows instead of 'expected' rows
+// InvalidName     'name' cannot be used, for the reason given
+// MissingKey      a JSON object has no member named 'key'
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Io { file: String, message: String },
+    UnexpectedEof { position: Position, expected: String },
+    InvalidValue { position: Position, token: String, expected: String },
+    WrongCount { position: Position, context: String, items: &'static str, expected: usize, found: usize },
+    WrongRowCount { position: Position, context: String, expected: usize, found: usize },
+    InvalidName { position: Position, name: String, reason: String },
+    MissingKey { position: Position, key: String },
+}
+
+impl ParseError {
+
+    //the position of the error in the file, if it has one
+    pub fn position(&self) -> Option<&Position> {
+        match self {
+            ParseError::Io { .. } => None,
+            ParseError::UnexpectedEof { position, .. }
+            | ParseError::InvalidValue { position, .. }
+            | ParseError::WrongCount { position, .. }
+            | ParseError::WrongRowCount { position, .. }
+            | ParseError::InvalidName { position, .. }
+            | ParseError::MissingKey { position, .. } => Some(position),
+        }
+    }
+
+    //the error message, without the position
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::Io { message, .. } => message.clone(),
+            ParseError::UnexpectedEof { expected, .. } => {
+                format!("unexpected end of file, expected {}", expected)
+            }
+            ParseError::InvalidValue { token, expected, .. } => {
+                format!("expected {}, found '{}'", expected, token.escape_debug())
+            }
+            ParseError::WrongCount { context, items, expected, found, .. } => {
+                format!("{}: expected {} {}, found {}", context, expected, items, found)
+            }
+            ParseError::WrongRowCount { context, expected, found, .. } => {
+                format!("{}: expected {} rows, found {}", context, expected, found)
+            }
+            ParseError::InvalidName { name, reason, .. } => format!("invalid name '{}': {}", name, reason),
+            ParseError::MissingKey { key, .. } => format!("missing key '{}'", key),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.position()) {
+            (ParseError::Io { file, message }, _) => write!(f, "{}: {}", file, message),
+            (_, Some(position)) => {
+                write!(f, "{}:{}:{}: {}", position.file, position.line, position.column, self.message())
+            }
+            (_, None) => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl Error for ParseError {}
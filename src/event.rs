@@ -0,0 +1,57 @@
+//*********************************************************
+//
+// Events
+//
+// Defines the events that can be applied, in order, to a
+// state: resource requests and resource releases.
+//
+//*********************************************************
+
+//*********************************************************
+//
+// EventKind Enum
+//
+// The kind of an event.
+//
+// Variants
+// --------
+// Request   the process asks for more resources
+// Release   the process gives back resources it holds
+//
+//*********************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Request,
+    Release,
+}
+
+impl EventKind {
+
+    //name of the event's vector, as printed (e.g. "The Request Vector is:")
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Request => "Request",
+            EventKind::Release => "Release",
+        }
+    }
+}
+
+//*********************************************************
+//
+// Event Struct
+//
+// A request or release of resources by a process.
+//
+// Fields
+// ------
+// kind         EventKind   request or release
+// process_id   usize       process ID of the requesting/releasing process
+// vector       Vec<i32>    instances of each resource type requested/released
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub process_id: usize,
+    pub vector: Vec<i32>,
+}
@@ -0,0 +1,119 @@
+//*********************************************************
+//
+// Scenario Generation
+//
+// Generates random states for trying out the algorithm.
+// Every generated state is consistent and safe by
+// construction. The random numbers come from a small
+// seeded generator, so a seed always gives the same state.
+//
+//*********************************************************
+use crate::state::BankerState;
+
+//*********************************************************
+//
+// Rng Struct
+//
+// A xorshift64* pseudo-random number generator. Not
+// suitable for anything but generating test scenarios.
+//
+// Fields
+// ------
+// state   u64   current state of the generator (never 0)
+//
+//*********************************************************
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+
+    //creates a generator from 'seed'
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: (seed ^ 0x9e37_79b9_7f4a_7c15) | 1 }
+    }
+
+    //the next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    //a random value from 'low' to 'high', inclusive
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        if high <= low {
+            return low;
+        }
+        low + (self.next_u64() % (high - low + 1) as u64) as i32
+    }
+
+    //a random permutation of 0..count
+    pub fn permutation(&mut self, count: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..count).collect();
+        for i in (1..count).rev() {
+            order.swap(i, (self.next_u64() % (i as u64 + 1)) as usize);
+        }
+        order
+    }
+}
+
+//*********************************************************
+//
+// Generate State Function
+//
+// Generates a random safe state. Each resource type gets a
+// random total, part of which is allocated to the
+// processes. The processes are then put in a random order,
+// and each one is given a need no larger than what would be
+// available once the processes before it have finished, so
+// that order is always a safe sequence.
+//
+// Return Value
+// ------------
+// BankerState   the generated state
+//
+// Function Parameters
+// -------------------
+// num_processes   usize   value   number of processes
+// num_resources   usize   value   number of resource types
+// seed            u64     value   seed of the random numbers
+//
+// Local Variables
+// ---------------
+// rng             Rng             random number generator
+// n               i32             number of processes (at least 1, for dividing)
+// resource_vec    Vec<i32>        total instances of each resource type
+// allocation      Vec<Vec<i32>>   resources allocated to each process
+// available_vec   Vec<i32>        instances of each resource type not allocated
+// max             Vec<Vec<i32>>   maximum demand of each process
+// work            Vec<i32>        resources available once the processes so far have finished
+//
+//*********************************************************
+pub fn generate_state(num_processes: usize, num_resources: usize, seed: u64) -> BankerState {
+    let mut rng = Rng::new(seed);
+    let n = num_processes.max(1) as i32;
+
+    //allocate at most an even share of each resource to every process
+    let resource_vec: Vec<i32> = (0..num_resources).map(|_| rng.range(n, 4 * n + 6)).collect();
+    let allocation: Vec<Vec<i32>> = (0..num_processes)
+        .map(|_| resource_vec.iter().map(|total| rng.range(0, total / n)).collect())
+        .collect();
+    let available_vec: Vec<i32> = resource_vec.iter().enumerate()
+        .map(|(j, total)| total - allocation.iter().map(|row| row[j]).sum::<i32>())
+        .collect();
+
+    //give each process, in a random order, a need the processes before it can cover
+    let mut max = allocation.clone();
+    let mut work = available_vec.clone();
+    for i in rng.permutation(num_processes) {
+        for j in 0..num_resources {
+            max[i][j] += rng.range(0, work[j]);
+            work[j] += allocation[i][j];
+        }
+    }
+
+    BankerState::new(resource_vec, available_vec, max, allocation)
+}
@@ -0,0 +1,900 @@
+//*********************************************************
+//
+// JSON
+//
+// A minimal JSON value type used for machine-readable
+// output, along with conversions of states and event
+// outcomes to JSON, and a parser for JSON input files that
+// keeps the position of every value so errors can point at
+// it. Written by hand so the crate keeps no dependencies
+// outside the standard library.
+//
+//*********************************************************
+use std::fmt;
+
+use crate::detect::Detection;
+use crate::error::{ParseError, Position};
+use crate::event::Event;
+use crate::names::Names;
+use crate::policy::SelectionPolicy;
+use crate::state::{BankerState, EventOutcome, ReleaseError, RequestOutcome, Safety, TraceStep};
+
+//*********************************************************
+//
+// Json Enum
+//
+// A JSON value. Object members keep the order in which
+// they were added.
+//
+// Variants
+// --------
+// Null      null
+// Bool      true or false
+// Number    an integer
+// String    a string
+// Array     an array of values
+// Object    an object, as a list of (key, value) members
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+
+    //an array of integers
+    pub fn vector(vector: &[i32]) -> Json {
+        Json::Array(vector.iter().map(|value| Json::Number(*value as i128)).collect())
+    }
+
+    //an array of arrays of integers
+    pub fn matrix(matrix: &[Vec<i32>]) -> Json {
+        Json::Array(matrix.iter().map(|row| Json::vector(row)).collect())
+    }
+
+    //an array of process IDs
+    pub fn processes(processes: &[usize]) -> Json {
+        Json::Array(processes.iter().map(|process| Json::Number(*process as i128)).collect())
+    }
+
+    //an array of strings
+    pub fn strings(strings: &[String]) -> Json {
+        Json::Array(strings.iter().map(|string| Json::String(string.clone())).collect())
+    }
+
+    //an object from (key, value) pairs
+    pub fn object(members: Vec<(&str, Json)>) -> Json {
+        Json::Object(members.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    //true for values that are not arrays or objects
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Json::Array(_) | Json::Object(_))
+    }
+
+    //*********************************************************
+    //
+    // Write Function
+    //
+    // Writes the value to 'out', indented by 'indent' levels.
+    // Arrays of scalars are written on one line so vectors and
+    // matrix rows stay readable; everything else is written one
+    // element per line.
+    //
+    // Return Value
+    // ------------
+    // none
+    //
+    // Function Parameters
+    // -------------------
+    // out      String   reference  output so far
+    // indent   usize    value      current indentation level
+    //
+    // Local Variables
+    // ---------------
+    // inner    String   indentation of the elements of an array or object
+    // items    Vec      written elements of an array of scalars
+    //
+    //*********************************************************
+    fn write(&self, out: &mut String, indent: usize) {
+        let inner = "  ".repeat(indent + 1);
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Number(value) => out.push_str(&value.to_string()),
+            Json::String(value) => write_string(out, value),
+            Json::Array(elements) if elements.is_empty() => out.push_str("[]"),
+            Json::Array(elements) if elements.iter().all(Json::is_scalar) => {
+                let items: Vec<String> = elements.iter().map(|element| element.to_string()).collect();
+                out.push_str(&format!("[{}]", items.join(", ")));
+            }
+            Json::Array(elements) => {
+                out.push_str("[\n");
+                for (i, element) in elements.iter().enumerate() {
+                    out.push_str(&inner);
+                    element.write(out, indent + 1);
+                    out.push_str(if i + 1 < elements.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push(']');
+            }
+            Json::Object(members) if members.is_empty() => out.push_str("{}"),
+            Json::Object(members) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in members.iter().enumerate() {
+                    out.push_str(&inner);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        write!(f, "{}", out)
+    }
+}
+
+//writes 'value' as a quoted JSON string, escaping as needed
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+//*********************************************************
+//
+// State To JSON Function
+//
+// Converts a state to a JSON object holding its resource
+// and available vectors and its max, allocation and need
+// matrices.
+//
+// Return Value
+// ------------
+// Json   the state
+//
+// Function Parameters
+// -------------------
+// state   BankerState   reference  state to convert
+//
+// Local Variables
+// ---------------
+// none
+//
+//*********************************************************
+pub fn state_to_json(state: &BankerState) -> Json {
+    Json::object(vec![
+        ("resource", Json::vector(state.resource_vec())),
+        ("available", Json::vector(state.available_vec())),
+        ("max", Json::matrix(state.max_matrix())),
+        ("allocation", Json::matrix(state.allocation_matrix())),
+        ("need", Json::matrix(state.need_matrix())),
+    ])
+}
+
+//*********************************************************
+//
+// Names To JSON Function
+//
+// Converts the names of a state's resource types and
+// processes to a JSON object. Unnamed resource types and
+// processes get their default labels ("A", "P0", etc.).
+//
+// Return Value
+// ------------
+// Json   the names
+//
+// Function Parameters
+// -------------------
+// state   BankerState   reference  state whose names to convert
+//
+// Local Variables
+// ---------------
+// names   Names   names of the state
+//
+//*********************************************************
+pub fn names_to_json(state: &BankerState) -> Json {
+    let names = state.names();
+    let processes: Vec<String> = (0..state.num_processes()).map(|process| names.process(process)).collect();
+    Json::object(vec![
+        ("resources", Json::strings(&names.resource_labels(state.num_resources()))),
+        ("processes", Json::strings(&processes)),
+    ])
+}
+
+//converts a selection policy to a JSON object: its name, and its values if it has any
+pub fn policy_to_json(policy: &SelectionPolicy) -> Json {
+    let mut members = vec![("name", Json::String(policy.name().to_string()))];
+    if let Some(values) = policy.values() {
+        members.push(("values", Json::vector(values)));
+    }
+    Json::object(members)
+}
+
+//*********************************************************
+//
+// Safety To JSON Function
+//
+// Converts the result of the safety algorithm to a JSON
+// object: whether the state is safe, and either the safe
+// sequence or the processes that could and could not
+// finish, the final Work vector, each blocked process's
+// deficits, and the bottleneck resources.
+//
+// Return Value
+// ------------
+// Json   the verdict
+//
+// Function Parameters
+// -------------------
+// safety   Safety   reference  result of the safety algorithm
+//
+// Local Variables
+// ---------------
+// report   UnsafeReport      why the state is not safe
+// deficits Vec<Json>         each blocked process and its deficit of each resource type
+// summary  ResourceDeficit   current bottleneck resource
+//
+//*********************************************************
+pub fn safety_to_json(safety: &Safety) -> Json {
+    match safety {
+        Safety::Safe(sequence) => Json::object(vec![
+            ("safe", Json::Bool(true)),
+            ("safe_sequence", Json::processes(sequence)),
+        ]),
+        Safety::Unsafe(report) => {
+            let deficits = report.blocked.iter().zip(&report.deficits)
+                .map(|(process, deficit)| Json::object(vec![
+                    ("process", Json::Number(*process as i128)),
+                    ("deficit", Json::Array(deficit.iter().map(|amount| Json::Number(*amount as i128)).collect())),
+                ]))
+                .collect();
+            Json::object(vec![
+                ("safe", Json::Bool(false)),
+                ("safe_sequence", Json::Null),
+                ("finished", Json::processes(&report.finished)),
+                ("blocked", Json::processes(&report.blocked)),
+                ("work", Json::vector(&report.work)),
+                ("deficits", Json::Array(deficits)),
+                ("bottlenecks", Json::Array(report.resource_deficits().iter().map(|summary| Json::object(vec![
+                    ("resource", Json::Number(summary.resource as i128)),
+                    ("processes", Json::processes(&summary.processes)),
+                    ("largest", Json::Number(summary.largest as i128)),
+                    ("total", Json::Number(summary.total as i128)),
+                ])).collect())),
+            ])
+        }
+    }
+}
+
+//*********************************************************
+//
+// Detection To JSON Function
+//
+// Converts the result of the deadlock detection algorithm
+// to a JSON object: whether any process is deadlocked,
+// which processes are, the order in which the others could
+// finish, and the final Work vector.
+//
+// Return Value
+// ------------
+// Json   the verdict
+//
+// Function Parameters
+// -------------------
+// detection   Detection   reference  result of the detection algorithm
+//
+// Local Variables
+// ---------------
+// none
+//
+//*********************************************************
+pub fn detection_to_json(detection: &Detection) -> Json {
+    Json::object(vec![
+        ("deadlocked", Json::Bool(detection.is_deadlocked())),
+        ("deadlocked_processes", Json::processes(&detection.deadlocked)),
+        ("finished", Json::processes(&detection.finished)),
+        ("work", Json::vector(&detection.work)),
+    ])
+}
+
+//*********************************************************
+//
+// Trace To JSON Function
+//
+// Converts the steps of the safety algorithm recorded by
+// 'BankerState::trace_safety' to a JSON array, one object
+// per process examined.
+//
+// Return Value
+// ------------
+// Json   the trace
+//
+// Function Parameters
+// -------------------
+// trace   Vec<TraceStep>   reference  steps of the safety algorithm
+//
+// Local Variables
+// ---------------
+// step    TraceStep   current step
+//
+//*********************************************************
+pub fn trace_to_json(trace: &[TraceStep]) -> Json {
+    Json::Array(trace.iter().map(|step| Json::object(vec![
+        ("pass", Json::Number(step.pass as i128)),
+        ("process", Json::Number(step.process_id as i128)),
+        ("need", Json::vector(&step.need)),
+        ("work", Json::vector(&step.work)),
+        ("can_finish", Json::Bool(step.can_finish)),
+        ("new_work", step.new_work.as_deref().map_or(Json::Null, Json::vector)),
+    ])).collect())
+}
+
+//*********************************************************
+//
+// Outcome To JSON Function
+//
+// Converts the outcome of an event to a JSON object holding
+// whether it was granted, a short machine-readable code for
+// the outcome, and the reason it was denied (null if it was
+// granted).
+//
+// Return Value
+// ------------
+// Json   the decision
+//
+// Function Parameters
+// -------------------
+// outcome   EventOutcome   reference  outcome of the event
+// names     Names          reference  names used to describe the reason
+//
+// Local Variables
+// ---------------
+// code     &str   short name of the outcome (e.g. "granted", "must_wait")
+//
+//*********************************************************
+pub fn outcome_to_json(outcome: &EventOutcome, names: &Names) -> Json {
+    let code = match outcome {
+        EventOutcome::Request(RequestOutcome::Granted) | EventOutcome::Release(Ok(())) => "granted",
+        EventOutcome::Request(RequestOutcome::ExceedsClaim { .. }) => "exceeds_claim",
+        EventOutcome::Request(RequestOutcome::MustWait { .. }) => "must_wait",
+        EventOutcome::Request(RequestOutcome::WouldBeUnsafe { .. }) => "would_be_unsafe",
+        EventOutcome::Request(RequestOutcome::UnknownProcess { .. })
+        | EventOutcome::Release(Err(ReleaseError::UnknownProcess { .. })) => "unknown_process",
+        EventOutcome::Request(RequestOutcome::WrongLength { .. })
+        | EventOutcome::Release(Err(ReleaseError::WrongLength { .. })) => "wrong_length",
+        EventOutcome::Request(RequestOutcome::Negative { .. })
+        | EventOutcome::Release(Err(ReleaseError::Negative { .. })) => "negative",
+        EventOutcome::Release(Err(ReleaseError::ExceedsAllocation { .. })) => "exceeds_allocation",
+    };
+    Json::object(vec![
+        ("granted", Json::Bool(outcome.is_granted())),
+        ("outcome", Json::String(code.to_string())),
+        ("reason", outcome.reason(names).map_or(Json::Null, Json::String)),
+    ])
+}
+
+//*********************************************************
+//
+// Input To JSON Function
+//
+// Converts a state and the events to apply to it into the
+// JSON input format read by 'parse::read_json_input', the
+// JSON counterpart of 'serialize::write_input'.
+//
+// Return Value
+// ------------
+// Json   the input document
+//
+// Function Parameters
+// -------------------
+// state    BankerState   reference  state to convert
+// events   Vec<Event>    reference  events to apply to the state
+//
+// Local Variables
+// ---------------
+// names     Names       names of the resource types and processes
+// members   Vec         members of the document
+// process   Json        process of an event, by name if it is one of the named processes
+//
+//*********************************************************
+pub fn input_to_json(state: &BankerState, events: &[Event]) -> Json {
+    let names = state.names();
+    let mut members = vec![];
+    if names.resources.is_some() || names.processes.is_some() {
+        let mut list = vec![];
+        if let Some(resources) = &names.resources {
+            list.push(("resources", Json::strings(resources)));
+        }
+        if let Some(processes) = &names.processes {
+            list.push(("processes", Json::strings(processes)));
+        }
+        members.push(("names", Json::object(list)));
+    }
+    members.push(("resource", Json::vector(state.resource_vec())));
+    members.push(("available", Json::vector(state.available_vec())));
+    members.push(("max", Json::matrix(state.max_matrix())));
+    members.push(("allocation", Json::matrix(state.allocation_matrix())));
+    if let Some(request_matrix) = state.request_matrix() {
+        members.push(("requests", Json::matrix(request_matrix)));
+    }
+
+    let events = events.iter().map(|event| {
+        let process = match names.processes.as_ref().and_then(|processes| processes.get(event.process_id)) {
+            Some(name) => Json::String(name.clone()),
+            None => Json::Number(event.process_id as i128),
+        };
+        Json::object(vec![
+            ("kind", Json::String(event.kind.name().to_lowercase())),
+            ("process", process),
+            ("vector", Json::vector(&event.vector)),
+        ])
+    });
+    members.push(("events", Json::Array(events.collect())));
+    Json::object(members)
+}
+
+//*********************************************************
+//
+// JsonNode Struct
+//
+// A JSON value read from a file, along with where it was
+// found.
+//
+// Fields
+// ------
+// position   Position   position of the first character of the value
+// kind       NodeKind   the value itself
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonNode {
+    pub position: Position,
+    pub kind: NodeKind,
+}
+
+//*********************************************************
+//
+// NodeKind Enum
+//
+// The value of a JsonNode. Mirrors Json, except that the
+// elements of arrays and objects keep their positions.
+//
+// Variants
+// --------
+// Null      null
+// Bool      true or false
+// Number    an integer
+// String    a string
+// Array     an array of values
+// Object    an object, as a list of (key, value) members in file order
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<JsonNode>),
+    Object(Vec<(String, JsonNode)>),
+}
+
+impl JsonNode {
+
+    //what kind of value the node holds, as used in errors (e.g. "an array")
+    pub fn description(&self) -> &'static str {
+        match self.kind {
+            NodeKind::Null => "null",
+            NodeKind::Bool(_) => "a boolean",
+            NodeKind::Number(_) => "a number",
+            NodeKind::String(_) => "a string",
+            NodeKind::Array(_) => "an array",
+            NodeKind::Object(_) => "an object",
+        }
+    }
+
+    //an error reporting that the node is not 'expected'
+    pub fn mismatch(&self, expected: &str) -> ParseError {
+        ParseError::InvalidValue {
+            position: self.position.clone(),
+            token: self.description().to_string(),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+//*********************************************************
+//
+// JsonParser Struct
+//
+// Reads a JSON document one character at a time, keeping
+// track of the line and column of the next character.
+//
+// Fields
+// ------
+// file_name   String        name of the file being read
+// lines       Vec<String>   text of every line of the file
+// chars       Vec<char>     every character of the file
+// index       usize         index of the next character
+// line        usize         line of the next character (starting at 1)
+// column      usize         column of the next character (starting at 1)
+// depth       usize         number of arrays and objects the next character is nested in
+//
+//*********************************************************
+struct JsonParser {
+    file_name: String,
+    lines: Vec<String>,
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+//the deepest nesting of arrays and objects the parser reads; the input
+//format needs 3 levels, and 'parse_value' recurses once per level
+const MAX_DEPTH: usize = 64;
+
+impl JsonParser {
+
+    //position of the next character
+    fn position(&self) -> Position {
+        Position {
+            file: self.file_name.clone(),
+            line: self.line,
+            column: self.column,
+            text: self.lines.get(self.line - 1).cloned().unwrap_or_default(),
+        }
+    }
+
+    //the next character, without consuming it
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    //consumes the next character
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        }
+        else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    //skips whitespace up to the next token
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r')) {
+            self.advance();
+        }
+    }
+
+    //an error reporting that the next character is not 'expected'
+    fn unexpected(&self, expected: &str) -> ParseError {
+        match self.peek() {
+            Some(c) => ParseError::InvalidValue {
+                position: self.position(),
+                token: c.to_string(),
+                expected: expected.to_string(),
+            },
+            None => ParseError::UnexpectedEof { position: self.position(), expected: expected.to_string() },
+        }
+    }
+
+    //consumes 'expected', or reports an error if it is not next
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected(&format!("'{}'", expected)));
+        }
+        self.advance();
+        Ok(())
+    }
+
+    //*********************************************************
+    //
+    // Parse Value Function
+    //
+    // Parses the next value of the document, along with
+    // every value nested in it. Arrays and objects nested
+    // more than MAX_DEPTH deep are reported as an error
+    // rather than overflowing the stack.
+    //
+    // Return Value
+    // ------------
+    // Result<JsonNode, ParseError>   the value read, or the error encountered
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // position   Position        position of the value
+    // kind       NodeKind        the value read
+    // word       String          letters of a literal (true, false or null)
+    // elements   Vec<JsonNode>   elements of an array read so far
+    // members    Vec             members of an object read so far
+    // key        String          key of the current member
+    //
+    //*********************************************************
+    fn parse_value(&mut self) -> Result<JsonNode, ParseError> {
+        self.skip_whitespace();
+        let position = self.position();
+        if matches!(self.peek(), Some('{' | '[')) && self.depth == MAX_DEPTH {
+            return Err(self.unexpected(&format!("a value nested at most {} arrays or objects deep", MAX_DEPTH)));
+        }
+        let kind = match self.peek() {
+            Some('{') => {
+                self.advance();
+                self.depth += 1;
+                let mut members: Vec<(String, JsonNode)> = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.advance();
+                }
+                else {
+                    loop {
+                        self.skip_whitespace();
+                        if self.peek() != Some('"') {
+                            return Err(self.unexpected("a string key"));
+                        }
+                        let key = self.parse_string()?;
+                        self.expect(':')?;
+                        members.push((key, self.parse_value()?));
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(',') => {
+                                self.advance();
+                            }
+                            Some('}') => {
+                                self.advance();
+                                break;
+                            }
+                            _ => return Err(self.unexpected("',' or '}'")),
+                        }
+                    }
+                }
+                self.depth -= 1;
+                NodeKind::Object(members)
+            }
+            Some('[') => {
+                self.advance();
+                self.depth += 1;
+                let mut elements = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.advance();
+                }
+                else {
+                    loop {
+                        elements.push(self.parse_value()?);
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(',') => {
+                                self.advance();
+                            }
+                            Some(']') => {
+                                self.advance();
+                                break;
+                            }
+                            _ => return Err(self.unexpected("',' or ']'")),
+                        }
+                    }
+                }
+                self.depth -= 1;
+                NodeKind::Array(elements)
+            }
+            Some('"') => NodeKind::String(self.parse_string()?),
+            //a '+' is not JSON, but is read as a number so the error shows the whole token
+            Some(c) if c == '-' || c == '+' || c.is_ascii_digit() => NodeKind::Number(self.parse_number()?),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = self.peek().filter(char::is_ascii_alphanumeric) {
+                    word.push(c);
+                    self.advance();
+                }
+                match word.as_str() {
+                    "true" => NodeKind::Bool(true),
+                    "false" => NodeKind::Bool(false),
+                    "null" => NodeKind::Null,
+                    _ => return Err(ParseError::InvalidValue { position, token: word, expected: "a JSON value".to_string() }),
+                }
+            }
+            _ => return Err(self.unexpected("a JSON value")),
+        };
+        Ok(JsonNode { position, kind })
+    }
+
+    //*********************************************************
+    //
+    // Parse String Function
+    //
+    // Parses a quoted string, starting at its opening quote
+    // and resolving escape sequences. A character outside
+    // the Basic Multilingual Plane may be escaped as a
+    // UTF-16 surrogate pair ("\ud83d\ude00"), as most JSON
+    // writers do.
+    //
+    // Return Value
+    // ------------
+    // Result<String, ParseError>   the string read, or the error encountered
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // value      String     the string read so far
+    // escape     Position   position of the current escape sequence
+    // code       u32        code point of a '\u' escape (or of the pair, for a surrogate pair)
+    // low        u32        the low surrogate following a high one
+    //
+    //*********************************************************
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.advance();
+        let mut value = String::new();
+        loop {
+            let escape = self.position();
+            match self.advance() {
+                None | Some('\n') => {
+                    return Err(ParseError::UnexpectedEof { position: escape, expected: "the end of the string".to_string() });
+                }
+                Some('"') => return Ok(value),
+                Some('\\') => {
+                    let c = match self.advance() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = self.parse_hex_escape(&escape)?;
+
+                            //a high surrogate must be followed by an escaped low surrogate
+                            if (0xd800..0xdc00).contains(&code) {
+                                let low = match (self.advance(), self.advance()) {
+                                    (Some('\\'), Some('u')) => self.parse_hex_escape(&escape)?,
+                                    _ => 0,
+                                };
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(ParseError::InvalidValue {
+                                        position: escape,
+                                        token: format!("\\u{:04x}", code),
+                                        expected: "a high surrogate followed by a low surrogate ('\\udc00' to '\\udfff')".to_string(),
+                                    });
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            match char::from_u32(code) {
+                                Some(c) => c,
+                                None => {
+                                    return Err(ParseError::InvalidValue {
+                                        position: escape,
+                                        token: format!("\\u{:04x}", code),
+                                        expected: "a unicode escape that is not a lone low surrogate".to_string(),
+                                    });
+                                }
+                            }
+                        }
+                        other => {
+                            return Err(ParseError::InvalidValue {
+                                position: escape,
+                                token: format!("\\{}", other.map(String::from).unwrap_or_default()),
+                                expected: "an escape sequence".to_string(),
+                            });
+                        }
+                    };
+                    value.push(c);
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    //reads the four hex digits of a '\u' escape starting at 'escape'
+    fn parse_hex_escape(&mut self, escape: &Position) -> Result<u32, ParseError> {
+        let digits: String = (0..4).filter_map(|_| self.advance()).collect();
+        match digits.len() == 4 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => Ok(u32::from_str_radix(&digits, 16).expect("four hex digits")),
+            false => Err(ParseError::InvalidValue {
+                position: escape.clone(),
+                token: format!("\\u{}", digits),
+                expected: "a unicode escape".to_string(),
+            }),
+        }
+    }
+
+    //parses an integer; fractions and exponents are not used by the input format, and JSON
+    //allows neither a '+' sign nor leading zeros, which Rust's own parsing would accept
+    fn parse_number(&mut self) -> Result<i64, ParseError> {
+        let position = self.position();
+        let mut token = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+            token.push(c);
+            self.advance();
+        }
+        let digits = token.strip_prefix('-').unwrap_or(&token);
+        let valid = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) && (digits == "0" || !digits.starts_with('0'));
+        match token.parse() {
+            Ok(value) if valid => Ok(value),
+            _ => Err(ParseError::InvalidValue { position, token, expected: "an integer".to_string() }),
+        }
+    }
+}
+
+//*********************************************************
+//
+// Parse JSON Function
+//
+// Parses a whole JSON document, reporting the position of
+// the first syntax error if it is not valid.
+//
+// Return Value
+// ------------
+// Result<JsonNode, ParseError>   the document, or the error encountered
+//
+// Function Parameters
+// -------------------
+// text        &str   reference  text of the document
+// file_name   &str   reference  name of the file (used in errors)
+//
+// Local Variables
+// ---------------
+// parser     JsonParser   parser over the text
+// document   JsonNode     the value read
+//
+//*********************************************************
+pub fn parse_json(text: &str, file_name: &str) -> Result<JsonNode, ParseError> {
+    let mut parser = JsonParser {
+        file_name: file_name.to_string(),
+        lines: text.lines().map(str::to_string).collect(),
+        chars: text.chars().collect(),
+        index: 0,
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+    let document = parser.parse_value()?;
+
+    //nothing but whitespace may follow the document
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.unexpected("the end of the file"));
+    }
+    Ok(document)
+}
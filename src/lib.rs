@@ -0,0 +1,49 @@
+//*********************************************************
+//
+// Banker's Algorithm Library
+//
+// Exposes the core of the Banker's Algorithm so it can be
+// embedded in other programs. The 'banker' binary is a
+// thin command line client of this library.
+//
+// Modules
+// -------
+// state     the BankerState type and the algorithm itself
+// detect    the deadlock detection algorithm, over a Request matrix
+// validate  checks of parsed input against its declared dimensions,
+//           and of a state against its invariants
+// parse     readers for the blank-line-separated text format and its JSON form
+// serialize writing of a state back into the text format
+// display   printing of labeled vectors and matrices as plain or bordered tables
+// error     errors reported while reading input files
+// event     requests and releases applied to a state
+// names     names of resource types and processes
+// generate  random safe states for trying out the algorithm
+// json      JSON output of states and event outcomes, and a JSON parser
+// policy    how the safety algorithm picks the next process to finish
+// repl      an interactive session for exploring a state
+// sequences every safe sequence of a state, listed or counted
+//
+//*********************************************************
+
+pub mod detect;
+pub mod display;
+pub mod error;
+pub mod event;
+pub mod generate;
+pub mod json;
+pub mod names;
+pub mod parse;
+pub mod policy;
+pub mod repl;
+pub mod sequences;
+pub mod serialize;
+pub mod state;
+pub mod validate;
+
+pub use detect::{detect_deadlock, Detection};
+pub use error::ParseError;
+pub use event::{Event, EventKind};
+pub use names::Names;
+pub use policy::SelectionPolicy;
+pub use state::{BankerState, EventOutcome, PendingRequest, ReleaseError, RequestOutcome, ResourceDeficit, Safety, ShapeError, TraceStep, UnsafeReport};
@@ -0,0 +1,137 @@
+//*********************************************************
+//
+// Names
+//
+// Defines the names given to resource types and processes.
+// Resource types without names are labeled like
+// spreadsheet columns ('A', 'B', ..., 'AA', ...), and
+// processes without names by their process ID.
+//
+//*********************************************************
+
+//*********************************************************
+//
+// Resource Label Function
+//
+// Returns the label of the resource type in column 'index'.
+// Labels are numbered like spreadsheet columns: 'A' to 'Z',
+// then 'AA', 'AB', and so on.
+//
+// Return Value
+// ------------
+// String    the resource label
+//
+// Function Parameters
+// -------------------
+// index    usize   value   column of the resource type (starting at 0)
+//
+// Local Variables
+// ---------------
+// label    Vec<u8>   letters of the label, built from last to first
+// n        usize     remaining (1-based) column number
+//
+//*********************************************************
+pub fn resource_label(index: usize) -> String {
+    let mut label = vec![];
+    let mut n = index + 1;
+    while n > 0 {
+        label.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
+
+//*********************************************************
+//
+// Names Struct
+//
+// Optional names for the resource types (columns) and
+// processes (rows) of a state.
+//
+// Fields
+// ------
+// resources   Option<Vec<String>>   name of each resource type, if named
+// processes   Option<Vec<String>>   name of each process, if named
+//
+//*********************************************************
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Names {
+    pub resources: Option<Vec<String>>,
+    pub processes: Option<Vec<String>>,
+}
+
+impl Names {
+
+    //name of the resource type in column 'index' (e.g. "gpu", or "A" if unnamed)
+    pub fn resource(&self, index: usize) -> String {
+        match &self.resources {
+            Some(resources) => resources[index].clone(),
+            None => resource_label(index),
+        }
+    }
+
+    //name of the process in row 'index', as used in messages (e.g. "etl-nightly", or "P0" if unnamed
+    //or past the named processes)
+    pub fn process(&self, index: usize) -> String {
+        match self.processes.as_ref().and_then(|processes| processes.get(index)) {
+            Some(name) => name.clone(),
+            None => format!("P{}", index),
+        }
+    }
+
+    //label of the process in row 'index', as printed beside matrix rows
+    //and in request lines (e.g. "etl-nightly", "0" if unnamed, or "P7" past the named processes)
+    pub fn process_row(&self, index: usize) -> String {
+        match &self.processes {
+            Some(processes) => processes.get(index).cloned().unwrap_or_else(|| format!("P{}", index)),
+            None => index.to_string(),
+        }
+    }
+
+    //names of the first 'count' resource types
+    pub fn resource_labels(&self, count: usize) -> Vec<String> {
+        (0..count).map(|index| self.resource(index)).collect()
+    }
+
+    //row labels of the first 'count' processes
+    pub fn process_rows(&self, count: usize) -> Vec<String> {
+        (0..count).map(|index| self.process_row(index)).collect()
+    }
+
+    //*********************************************************
+    //
+    // Find Process Function
+    //
+    // Finds the process referred to by 'token', which is
+    // the name of a process, its process ID, or its process
+    // ID written as in messages ("P2"). When the processes
+    // are named, an ID past the last of them refers to no
+    // process.
+    //
+    // Return Value
+    // ------------
+    // Option<usize>   the process ID, or None if 'token' refers to no process
+    //
+    // Function Parameters
+    // -------------------
+    // token   &str   reference  process name or ID
+    //
+    // Local Variables
+    // ---------------
+    // index   usize   the process ID written in 'token'
+    //
+    //*********************************************************
+    pub fn find_process(&self, token: &str) -> Option<usize> {
+        if let Some(processes) = &self.processes {
+            if let Some(index) = processes.iter().position(|name| name == token) {
+                return Some(index);
+            }
+        }
+        let index: usize = token.parse().ok().or_else(|| token.strip_prefix('P')?.parse().ok())?;
+        match &self.processes {
+            Some(processes) if index >= processes.len() => None,
+            _ => Some(index),
+        }
+    }
+}
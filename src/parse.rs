@@ -0,0 +1,1123 @@
+//*********************************************************
+//
+// Input Parsing Functions
+//
+// Reads the sections of the blank-line-separated text
+// input format: the process and resource counts, optional
+// 'resources:' and 'processes:' lines naming the columns
+// and rows, the resource vector, the available vector, the
+// max and allocation matrices, an optional Request matrix
+// for deadlock detection (introduced by a 'requests:'
+// line), and any number of event lines, each a request ('0:1 0 1', 'request 0:1 0 1' or
+// 'etl-nightly: 1 0 1') or a release ('release 0:1 0 0')
+// applied in order, as well as the same input written as
+// a JSON object. Every reader reports a ParseError
+// pointing at the offending line and column instead of
+// panicking.
+//
+// Values are separated by any whitespace, lines may end in
+// '\n' or '\r\n', and '#' starts a comment that runs to the
+// end of the line. Sections are found by their content, so
+// any number of blank lines may separate them.
+//
+// The readers do not check the number of values on a line
+// or the number of rows in a matrix; each value keeps its
+// position so that 'validate' can report every mismatch
+// with the declared dimensions at once.
+//
+//*********************************************************
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::{ParseError, Position};
+use crate::event::{Event, EventKind};
+use crate::json::{parse_json, JsonNode, NodeKind};
+use crate::names::Names;
+use crate::state::BankerState;
+
+//*********************************************************
+//
+// Row Struct
+//
+// A line of integers read from the file, along with where
+// it was found.
+//
+// Fields
+// ------
+// position   Position    position of the line (column of its first value)
+// values     Vec<i32>    values read from the line
+// columns    Vec<usize>  column at which each value starts
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub position: Position,
+    pub values: Vec<i32>,
+    pub columns: Vec<usize>,
+}
+
+//*********************************************************
+//
+// MatrixRows Struct
+//
+// The rows of a matrix read from the file.
+//
+// Fields
+// ------
+// rows   Vec<Row>    rows of the matrix, in file order
+// end    Position    position of the line that ended the matrix (blank line or end of file)
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixRows {
+    pub rows: Vec<Row>,
+    pub end: Position,
+}
+
+//*********************************************************
+//
+// NameList Struct
+//
+// A line of names read from the file, along with where it
+// was found.
+//
+// Fields
+// ------
+// position   Position     position of the line (column of its first name)
+// names      Vec<String>  names read from the line
+// columns    Vec<usize>   column at which each name starts
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameList {
+    pub position: Position,
+    pub names: Vec<String>,
+    pub columns: Vec<usize>,
+}
+
+//*********************************************************
+//
+// EventRow Struct
+//
+// An event line read from the file.
+//
+// Fields
+// ------
+// kind         EventKind   request or release
+// process_id   usize       process ID given on the line
+// row          Row         values of the event's vector, with their position
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRow {
+    pub kind: EventKind,
+    pub process_id: usize,
+    pub row: Row,
+}
+
+impl EventRow {
+
+    //the event described by the line
+    pub fn to_event(&self) -> Event {
+        Event { kind: self.kind, process_id: self.process_id, vector: self.row.values.clone() }
+    }
+}
+
+//*********************************************************
+//
+// ParsedInput Struct
+//
+// Everything read from an input file, before it has been
+// checked against the declared dimensions.
+//
+// Fields
+// ------
+// num_processes   usize              declared number of processes
+// num_resources   usize              declared number of resource types
+// resource_names  Option<NameList>   names of the resource types, if given
+// process_names   Option<NameList>   names of the processes, if given
+// resource        Row                resource vector
+// available       Row                available vector
+// max             MatrixRows         max matrix
+// allocation      MatrixRows         allocation matrix
+// requests        Option<MatrixRows> request matrix of what each process is blocked on, if given
+// events          Vec<EventRow>      request and release lines, in file order
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedInput {
+    pub num_processes: usize,
+    pub num_resources: usize,
+    pub resource_names: Option<NameList>,
+    pub process_names: Option<NameList>,
+    pub resource: Row,
+    pub available: Row,
+    pub max: MatrixRows,
+    pub allocation: MatrixRows,
+    pub requests: Option<MatrixRows>,
+    pub events: Vec<EventRow>,
+}
+
+impl ParsedInput {
+
+    //names of the resource types and processes given in the input
+    pub fn names(&self) -> Names {
+        Names {
+            resources: self.resource_names.as_ref().map(|list| list.names.clone()),
+            processes: self.process_names.as_ref().map(|list| list.names.clone()),
+        }
+    }
+
+    //*********************************************************
+    //
+    // Into State Function
+    //
+    // Builds the state of the system from the parsed input,
+    // including the names of its resource types and
+    // processes and its Request matrix, if given. The input
+    // must already have passed 'validate::check_dimensions',
+    // which reports any size mismatch with its position.
+    //
+    // Return Value
+    // ------------
+    // BankerState    the state of the system
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // names    Names     names of the resource types and processes
+    // values   closure   extracts the values of each row of a matrix
+    //
+    //*********************************************************
+    pub fn into_state(self) -> BankerState {
+        let names = self.names();
+        let values = |matrix: MatrixRows| matrix.rows.into_iter().map(|row| row.values).collect();
+        let state = BankerState::new(self.resource.values, self.available.values, values(self.max), values(self.allocation))
+            .with_names(names)
+            .expect("the names were checked by 'check_dimensions'");
+        match self.requests {
+            Some(requests) => state.with_request_matrix(values(requests)).expect("the Request matrix was checked by 'check_dimensions'"),
+            None => state,
+        }
+    }
+}
+
+//*********************************************************
+//
+// LineReader Struct
+//
+// Wraps a buffered reader (a file, stdin, or anything else
+// that implements BufRead) and keeps track of the file name
+// and the number of the line that was last read, so that
+// errors can be reported with their position.
+//
+// Fields
+// ------
+// reader        R                buffered reader of the input
+// file_name     String           name of the file being read
+// line_number   usize            number of the last line read (0 before the first line)
+// line          String           text of the last line read, without the line ending
+// replay        bool             whether the last line read should be returned again
+//
+//*********************************************************
+pub struct LineReader<R: BufRead> {
+    reader: R,
+    file_name: String,
+    line_number: usize,
+    line: String,
+    replay: bool,
+}
+
+impl<R: BufRead> LineReader<R> {
+
+    //creates a line reader over 'reader', reporting errors against 'file_name'
+    pub fn new(reader: R, file_name: &str) -> LineReader<R> {
+        LineReader { reader, file_name: file_name.to_string(), line_number: 0, line: String::new(), replay: false }
+    }
+
+    //*********************************************************
+    //
+    // Next Line Function
+    //
+    // Reads the next line of the file, with the line ending
+    // ('\n' or '\r\n') and any '#' comment removed. Lines
+    // holding only a comment are skipped, as is a byte order
+    // mark at the start of the file. Reaching the end of the
+    // file is an error, as the caller expected 'expected' to
+    // follow.
+    //
+    // Return Value
+    // ------------
+    // Result<String, ParseError>   the line read, or the error encountered
+    //
+    // Function Parameters
+    // -------------------
+    // expected   &str   reference  what the caller expected to read (used in the error)
+    //
+    // Local Variables
+    // ---------------
+    // bytes_read   usize   number of bytes read from the file
+    //
+    //*********************************************************
+    fn next_line(&mut self, expected: &str) -> Result<String, ParseError> {
+
+        //return the last line again if it was put back
+        if self.replay {
+            self.replay = false;
+            return Ok(self.content());
+        }
+
+        loop {
+            self.line.clear();
+            let bytes_read = self.reader.read_line(&mut self.line).map_err(|error| ParseError::Io {
+                file: self.file_name.clone(),
+                message: error.to_string(),
+            })?;
+
+            //the end of the file is reported on the line after the last one
+            if bytes_read == 0 {
+                self.line_number += 1;
+                return Err(ParseError::UnexpectedEof { position: self.position(1), expected: expected.to_string() });
+            }
+
+            self.line_number += 1;
+            let trimmed_len = self.line.trim_end_matches(['\n', '\r']).len();
+            self.line.truncate(trimmed_len);
+            if self.line_number == 1 && self.line.starts_with('\u{feff}') {
+                self.line.remove(0);
+            }
+
+            //lines holding only a comment are not part of any section
+            if !self.line.trim_start().starts_with('#') {
+                return Ok(self.content());
+            }
+        }
+    }
+
+    //text of the last line read, up to any '#' comment
+    fn content(&self) -> String {
+        match self.line.find('#') {
+            Some(index) => self.line[..index].to_string(),
+            None => self.line.clone(),
+        }
+    }
+
+    //*********************************************************
+    //
+    // Try Next Line Function
+    //
+    // Reads the next line of the file like 'next_line', but
+    // returns None instead of an error at the end of the file.
+    //
+    // Return Value
+    // ------------
+    // Result<Option<String>, ParseError>   the line read (None at the end of the file), or the error encountered
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // none
+    //
+    //*********************************************************
+    fn try_next_line(&mut self) -> Result<Option<String>, ParseError> {
+        match self.next_line("") {
+            Ok(line) => Ok(Some(line)),
+            Err(ParseError::UnexpectedEof { .. }) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    //puts the last line read back, so that the next read returns it again
+    fn unread_line(&mut self) {
+        self.replay = true;
+    }
+
+    //reads the next line that is not blank, or None at the end of the file
+    fn next_nonblank_line(&mut self) -> Result<Option<String>, ParseError> {
+        while let Some(line) = self.try_next_line()? {
+            if !line.trim().is_empty() {
+                return Ok(Some(line));
+            }
+        }
+        Ok(None)
+    }
+
+    //position of the given column on the last line read
+    fn position(&self, column: usize) -> Position {
+        Position {
+            file: self.file_name.clone(),
+            line: self.line_number,
+            column,
+            text: self.line.clone(),
+        }
+    }
+
+    //position just past the end of the last line read
+    fn end_position(&self) -> Position {
+        self.position(self.line.chars().count() + 1)
+    }
+}
+
+//*********************************************************
+//
+// Split Tokens Function
+//
+// Splits a line into its tokens, separated by any amount of
+// whitespace (spaces or tabs), along with the column at
+// which each token starts.
+//
+// Return Value
+// ------------
+// Vec<(usize, &str)>   column (starting at 1) and text of each token
+//
+// Function Parameters
+// -------------------
+// line     &str    reference  line to split
+// offset   usize   value      number of characters preceding 'line' on its line
+//
+// Local Variables
+// ---------------
+// tokens   Vec<(usize, &str)>   tokens found so far
+// start    Option<(usize, usize)>  byte index and column of the current token, if inside one
+// column   usize                column of the current character
+// index    usize                byte index of the current character
+// c        char                 current character
+//
+//*********************************************************
+fn split_tokens(line: &str, offset: usize) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    let mut column = offset;
+
+    for (index, c) in line.char_indices() {
+        column += 1;
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((index, column)),
+            (true, Some((begin, begin_column))) => {
+                tokens.push((begin_column, &line[begin..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((begin, begin_column)) = start {
+        tokens.push((begin_column, &line[begin..]));
+    }
+    tokens
+}
+
+//*********************************************************
+//
+// Parse Token Function
+//
+// Parses a single token into a value, reporting an error
+// at the token's position if it is not valid.
+//
+// Return Value
+// ------------
+// Result<T, ParseError>   the parsed value, or the error encountered
+//
+// Function Parameters
+// -------------------
+// reader     LineReader   reference  reader that read the token (used for its position)
+// column     usize        value      column at which the token starts
+// token      &str         reference  token to parse
+// expected   &str         reference  description of a valid token (used in the error)
+//
+// Local Variables
+// ---------------
+// none
+//
+//*********************************************************
+fn parse_token<T: FromStr, R: BufRead>(reader: &LineReader<R>, column: usize, token: &str, expected: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::InvalidValue {
+        position: reader.position(column),
+        token: token.to_string(),
+        expected: expected.to_string(),
+    })
+}
+
+//*********************************************************
+//
+// Parse Row Function
+//
+// Parses every token of a line into a row of integers.
+//
+// Return Value
+// ------------
+// Result<Row, ParseError>   the parsed row, or the error encountered
+//
+// Function Parameters
+// -------------------
+// reader    LineReader   reference  reader that read the line (used for its position)
+// line      &str         reference  line (or part of a line) holding the values
+// offset    usize        value      number of characters preceding 'line' on its line
+//
+// Local Variables
+// ---------------
+// tokens    Vec<(usize, &str)>   tokens of the line with their columns
+// values    Vec<i32>             values parsed from the tokens
+// columns   Vec<usize>           column of each value
+//
+//*********************************************************
+fn parse_row<R: BufRead>(reader: &LineReader<R>, line: &str, offset: usize) -> Result<Row, ParseError> {
+    let tokens = split_tokens(line, offset);
+    let values = tokens.iter()
+        .map(|(column, token)| parse_token(reader, *column, token, "an integer"))
+        .collect::<Result<Vec<i32>, ParseError>>()?;
+    let columns: Vec<usize> = tokens.iter().map(|(column, _)| *column).collect();
+    Ok(Row {
+        position: reader.position(columns.first().copied().unwrap_or(offset + 1)),
+        values,
+        columns,
+    })
+}
+
+//*********************************************************
+//
+// Read First Line Function
+//
+// Reads the first line of the file and returns the number
+// of processes and the number of resources. There must be
+// at least one resource type, as a vector of no values
+// would be a blank line, which separates the sections.
+//
+// Return Values (both returned as a tuple)
+// ------------
+// usize      number of processes
+// usize      number of resources
+//
+// Function Parameters
+// -------------------
+// reader   LineReader  reference  file reader
+//
+// Local Variables
+// ---------------
+// line           String              line read from file
+// parts          Vec<(usize, &str)>  line parts containing the number of processes and resources
+// num_processes  usize               number of processes
+// num_resources  usize               number of resources
+//
+//*********************************************************
+pub fn read_first_line<R: BufRead>(reader: &mut LineReader<R>) -> Result<(usize, usize), ParseError> {
+
+    //read the first line in full
+    let line = reader.next_line("the number of processes and resource types")?;
+
+    //initialize 'parts' to hold the line parts containing
+    //the number of processes and resources
+    let parts = split_tokens(&line, 0);
+    if parts.len() != 2 {
+        let column = parts.get(2).map_or(reader.end_position().column, |(column, _)| *column);
+        return Err(ParseError::WrongCount {
+            position: reader.position(column),
+            context: "the first line".to_string(),
+            items: "integers",
+            expected: 2,
+            found: parts.len(),
+        });
+    }
+
+    //read and store the number of processes and resources
+    let num_processes = parse_token(reader, parts[0].0, parts[0].1, "the number of processes")?;
+    let num_resources = parse_token(reader, parts[1].0, parts[1].1, "the number of resource types")?;
+    if num_resources == 0 {
+        return Err(ParseError::InvalidValue {
+            position: reader.position(parts[1].0),
+            token: parts[1].1.to_string(),
+            expected: "at least one resource type".to_string(),
+        });
+    }
+    Ok((num_processes, num_resources))
+}
+
+//*********************************************************
+//
+// Read Vector Function
+//
+// Skips over the blank lines preceding a vector and reads
+// the vector from the file.
+//
+// Return Value
+// ------------
+// Row      the vector read, with its position
+//
+// Function Parameters
+// -------------------
+// reader   LineReader  reference  file reader
+// name     &str        reference  type of vector (e.g. "Resource", "Available")
+//
+// Local Variables
+// ---------------
+// line     String      line read from file
+//
+//*********************************************************
+pub fn read_vector<R: BufRead>(reader: &mut LineReader<R>, name: &str) -> Result<Row, ParseError> {
+    match reader.next_nonblank_line()? {
+        Some(line) => parse_row(reader, &line, 0),
+        None => Err(ParseError::UnexpectedEof { position: reader.position(1), expected: format!("the {} vector", name) }),
+    }
+}
+
+//*********************************************************
+//
+// Read Names Function
+//
+// Reads the optional 'resources:' and 'processes:' lines
+// that may follow the first line, each giving one name per
+// resource type or process separated by spaces.
+//
+// Return Values (both returned as a tuple)
+// ------------
+// Option<NameList>   names of the resource types, if given
+// Option<NameList>   names of the processes, if given
+//
+// Function Parameters
+// -------------------
+// reader   LineReader  reference  file reader
+//
+// Local Variables
+// ---------------
+// resource_names  Option<NameList>    names of the resource types read so far
+// process_names   Option<NameList>    names of the processes read so far
+// line            String              line read from file
+// keyword         &str                keyword starting the line ("resources" or "processes")
+// rest            &str                part of the line after the keyword
+// tokens          Vec<(usize, &str)>  names on the line with their columns
+// list            NameList            names read from the line
+//
+//*********************************************************
+pub fn read_names<R: BufRead>(reader: &mut LineReader<R>) -> Result<(Option<NameList>, Option<NameList>), ParseError> {
+    let mut resource_names = None;
+    let mut process_names = None;
+
+    while let Some(line) = reader.next_nonblank_line()? {
+
+        //a line that does not start with a keyword begins the Resource vector
+        let (keyword, rest) = match line.trim_start().split_once(':') {
+            Some((keyword @ ("resources" | "processes"), rest)) => (keyword, rest),
+            _ => {
+                reader.unread_line();
+                break;
+            }
+        };
+
+        //read the names that follow the keyword
+        let offset = line.len() - rest.len();
+        let tokens = split_tokens(rest, line[..offset].chars().count());
+        let list = NameList {
+            position: reader.position(tokens.first().map_or(offset + 1, |(column, _)| *column)),
+            names: tokens.iter().map(|(_, name)| name.to_string()).collect(),
+            columns: tokens.iter().map(|(column, _)| *column).collect(),
+        };
+        if keyword == "resources" {
+            resource_names = Some(list);
+        }
+        else {
+            process_names = Some(list);
+        }
+    }
+    Ok((resource_names, process_names))
+}
+
+//*********************************************************
+//
+// Read Matrix Function
+//
+// Skips over the blank lines preceding a matrix and reads
+// rows until the next blank line, the first event line
+// (which holds a ':') or the end of the file.
+//
+// Return Value
+// ------------
+// MatrixRows      rows of the matrix, with their positions
+//
+// Function Parameters
+// -------------------
+// reader         LineReader  reference  file reader
+//
+// Local Variables
+// ---------------
+// rows      Vec<Row>       rows of the matrix read so far
+// line      String         used to read lines/rows from the file
+//
+//************************************************************
+pub fn read_matrix<R: BufRead>(reader: &mut LineReader<R>) -> Result<MatrixRows, ParseError> {
+
+    //initialize the rows that store our final result
+    let mut rows = vec![];
+
+    //store each line in the file as a row in the matrix, skipping the
+    //blank lines before it and stopping at the first blank line after
+    //it, an event line or the end of the file
+    while let Some(line) = reader.try_next_line()? {
+        if line.trim().is_empty() {
+            if rows.is_empty() {
+                continue;
+            }
+            break;
+        }
+        if line.contains(':') {
+            reader.unread_line();
+            break;
+        }
+        rows.push(parse_row(reader, &line, 0)?);
+    }
+    Ok(MatrixRows { rows, end: reader.position(1) })
+}
+
+//*********************************************************
+//
+// Read Event Function
+//
+// Reads the next event line from the file, skipping any
+// blank lines before it. A line holds an optional keyword
+// ('request' or 'release', default 'request'), the process
+// name or id, a ':' and the event's vector.
+//
+// Return Value
+// ------------
+// Option<EventRow>   the event read, or None at the end of the file
+//
+// Function Parameters
+// -------------------
+// reader          LineReader  reference  file reader
+// names           Names       reference  names of the processes, used to look up the process
+//
+// Local Variables
+// ---------------
+// line         String     stores the line read from the file
+// kind         EventKind  kind of event given by the keyword
+// rest         &str       part of the line after the keyword
+// colon        usize      byte index of the ':' separating the process id from the values
+// values       &str       part of the line holding the event's values
+// offset       usize      number of characters preceding 'values' on the line
+// process      &str       process name or id given on the line
+// process_id   usize      stores the process id of the requesting/releasing process
+//
+//**********************************************************
+pub fn read_event<R: BufRead>(reader: &mut LineReader<R>, names: &Names) -> Result<Option<EventRow>, ParseError> {
+
+    //skip over blank lines and read the event line, if there is one
+    let line = match reader.next_nonblank_line()? {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    //read the keyword giving the kind of event, if there is one
+    let trimmed = line.trim_start();
+    let (kind, rest) = match trimmed.split_once(char::is_whitespace) {
+        Some(("release", rest)) => (EventKind::Release, rest),
+        Some(("request", rest)) => (EventKind::Request, rest),
+        _ => (EventKind::Request, trimmed),
+    };
+    let rest_start = line.len() - rest.len();
+
+    //the process id is separated from the event's values by a ':'
+    let colon = match rest.find(':') {
+        Some(colon) => rest_start + colon,
+        None => {
+            return Err(ParseError::InvalidValue {
+                position: reader.position(1),
+                token: line.clone(),
+                expected: "'[request|release] <process>:<values>'".to_string(),
+            });
+        }
+    };
+
+    //look up the process by name or id, and parse the values that follow it
+    let process = line[rest_start..colon].trim();
+    let process_id = match names.find_process(process) {
+        Some(process_id) => process_id,
+        None => {
+            return Err(ParseError::InvalidValue {
+                position: reader.position(line[..rest_start].chars().count() + 1),
+                token: process.to_string(),
+                expected: "a process name or id".to_string(),
+            });
+        }
+    };
+    let values = line[colon + 1..].trim_start();
+    let offset = line.len() - values.len();
+    let row = parse_row(reader, values, line[..offset].chars().count())?;
+    Ok(Some(EventRow { kind, process_id, row }))
+}
+
+//*********************************************************
+//
+// Read Input Function
+//
+// Reads an entire input file: the first line, the optional
+// names of the resource types and processes, the resource
+// and available vectors, the max and allocation matrices,
+// the optional Request matrix and every event line that
+// follows them.
+//
+// Return Value
+// ------------
+// Result<ParsedInput, ParseError>   everything read from the file, or the first error encountered
+//
+// Function Parameters
+// -------------------
+// reader         LineReader  reference  file reader
+//
+// Local Variables
+// ---------------
+// num_processes  usize          number of processes
+// num_resources  usize          number of resource types
+// max            MatrixRows     max matrix
+// allocation     MatrixRows     allocation matrix
+// requests       Option<MatrixRows>  request matrix, if a 'requests:' line introduces one
+// resource_names Option<NameList>  names of the resource types, if given
+// process_names  Option<NameList>  names of the processes, if given
+// names          Names          names used to look up the process of each event
+// events         Vec<EventRow>  event lines read so far
+//
+//*********************************************************
+pub fn read_input<R: BufRead>(reader: &mut LineReader<R>) -> Result<ParsedInput, ParseError> {
+    let (num_processes, num_resources) = read_first_line(reader)?;
+    let (resource_names, process_names) = read_names(reader)?;
+    let resource = read_vector(reader, "Resource")?;
+    let available = read_vector(reader, "Available")?;
+    let mut max = read_matrix(reader)?;
+    let mut allocation = read_matrix(reader)?;
+
+    //without a blank line between them, the max and allocation matrices
+    //are read as one block; split it after the declared number of rows
+    if allocation.rows.is_empty() && num_processes > 0 && max.rows.len() == 2 * num_processes {
+        allocation.rows = max.rows.split_off(num_processes);
+        max.end = allocation.rows[0].position.clone();
+    }
+
+    //a 'requests:' line on its own introduces the Request matrix
+    let mut requests = None;
+    if let Some(line) = reader.next_nonblank_line()? {
+        match line.trim() == "requests:" {
+            true => requests = Some(read_matrix(reader)?),
+            false => reader.unread_line(),
+        }
+    }
+
+    let names = Names {
+        resources: None,
+        processes: process_names.as_ref().map(|list| list.names.clone()),
+    };
+    let mut events = vec![];
+    while let Some(event) = read_event(reader, &names)? {
+        events.push(event);
+    }
+
+    Ok(ParsedInput {
+        num_processes,
+        num_resources,
+        resource_names,
+        process_names,
+        resource,
+        available,
+        max,
+        allocation,
+        requests,
+        events,
+    })
+}
+
+//*********************************************************
+//
+// JSON Row Function
+//
+// Reads a JSON array of integers as a row, keeping the
+// position of each value. Values on a later line than the
+// array's opening bracket are placed at the bracket, since
+// a row's diagnostics only show one line.
+//
+// Return Value
+// ------------
+// Result<Row, ParseError>   the row read, or the error encountered
+//
+// Function Parameters
+// -------------------
+// node   JsonNode   reference  array to read
+//
+// Local Variables
+// ---------------
+// elements   Vec<JsonNode>   elements of the array
+// values     Vec<i32>        values read from the elements
+// columns    Vec<usize>      column of each value
+//
+//*********************************************************
+fn json_row(node: &JsonNode) -> Result<Row, ParseError> {
+    let elements = match &node.kind {
+        NodeKind::Array(elements) => elements,
+        _ => return Err(node.mismatch("an array of integers")),
+    };
+
+    let mut values = vec![];
+    let mut columns = vec![];
+    for element in elements {
+        match element.kind {
+            NodeKind::Number(value) => {
+                values.push(i32::try_from(value).map_err(|_| element.mismatch("an integer that fits in 32 bits"))?);
+            }
+            _ => return Err(element.mismatch("an integer")),
+        }
+        columns.push(match element.position.line == node.position.line {
+            true => element.position.column,
+            false => node.position.column,
+        });
+    }
+    Ok(Row { position: node.position.clone(), values, columns })
+}
+
+//reads a JSON array of arrays of integers as the rows of a matrix
+fn json_matrix(node: &JsonNode) -> Result<MatrixRows, ParseError> {
+    match &node.kind {
+        NodeKind::Array(elements) => Ok(MatrixRows {
+            rows: elements.iter().map(json_row).collect::<Result<Vec<Row>, ParseError>>()?,
+            end: node.position.clone(),
+        }),
+        _ => Err(node.mismatch("an array of arrays of integers")),
+    }
+}
+
+//reads a JSON array of strings as a list of names
+fn json_names(node: &JsonNode) -> Result<NameList, ParseError> {
+    let elements = match &node.kind {
+        NodeKind::Array(elements) => elements,
+        _ => return Err(node.mismatch("an array of names")),
+    };
+    let mut names = vec![];
+    for element in elements {
+        match &element.kind {
+            NodeKind::String(name) => names.push(name.clone()),
+            _ => return Err(element.mismatch("a name")),
+        }
+    }
+    let columns = elements.iter()
+        .map(|element| match element.position.line == node.position.line {
+            true => element.position.column,
+            false => node.position.column,
+        })
+        .collect();
+    Ok(NameList { position: node.position.clone(), names, columns })
+}
+
+//reads a JSON count (a non-negative integer)
+fn json_count(node: &JsonNode) -> Result<usize, ParseError> {
+    match node.kind {
+        NodeKind::Number(value) => usize::try_from(value).map_err(|_| node.mismatch("a non-negative integer")),
+        _ => Err(node.mismatch("a non-negative integer")),
+    }
+}
+
+//the members of a JSON object
+fn json_members(node: &JsonNode) -> Result<&[(String, JsonNode)], ParseError> {
+    match &node.kind {
+        NodeKind::Object(members) => Ok(members),
+        _ => Err(node.mismatch("an object")),
+    }
+}
+
+//*********************************************************
+//
+// JSON Event Function
+//
+// Reads an event from a JSON object with a 'kind'
+// ("request" or "release", default "request"), a
+// 'process' (name or ID) and a 'vector'.
+//
+// Return Value
+// ------------
+// Result<EventRow, ParseError>   the event read, or the error encountered
+//
+// Function Parameters
+// -------------------
+// node    JsonNode   reference  object to read
+// names   Names      reference  names of the processes, used to look up the process
+//
+// Local Variables
+// ---------------
+// kind         EventKind          kind of event
+// process_id   Option<usize>      process ID of the event, once read
+// row          Option<Row>        vector of the event, once read
+// key          String             key of the current member
+// value        JsonNode           value of the current member
+//
+//*********************************************************
+fn json_event(node: &JsonNode, names: &Names) -> Result<EventRow, ParseError> {
+    let mut kind = EventKind::Request;
+    let mut process_id = None;
+    let mut row = None;
+
+    for (key, value) in json_members(node)? {
+        match key.as_str() {
+            "kind" => {
+                kind = match &value.kind {
+                    NodeKind::String(kind) if kind == "request" => EventKind::Request,
+                    NodeKind::String(kind) if kind == "release" => EventKind::Release,
+                    _ => return Err(value.mismatch("\"request\" or \"release\"")),
+                };
+            }
+            "process" => {
+                let token = match &value.kind {
+                    NodeKind::String(name) => name.clone(),
+                    NodeKind::Number(id) => id.to_string(),
+                    _ => return Err(value.mismatch("a process name or id")),
+                };
+                process_id = match names.find_process(&token) {
+                    Some(process_id) => Some(process_id),
+                    None => {
+                        return Err(ParseError::InvalidValue {
+                            position: value.position.clone(),
+                            token,
+                            expected: "a process name or id".to_string(),
+                        });
+                    }
+                };
+            }
+            "vector" => row = Some(json_row(value)?),
+            _ => {
+                return Err(ParseError::InvalidValue {
+                    position: value.position.clone(),
+                    token: key.clone(),
+                    expected: "'kind', 'process' or 'vector'".to_string(),
+                });
+            }
+        }
+    }
+
+    let missing = |key: &str| ParseError::MissingKey { position: node.position.clone(), key: key.to_string() };
+    Ok(EventRow {
+        kind,
+        process_id: process_id.ok_or_else(|| missing("process"))?,
+        row: row.ok_or_else(|| missing("vector"))?,
+    })
+}
+
+//*********************************************************
+//
+// Read JSON Input Function
+//
+// Reads an entire input file written as a JSON object
+// instead of in the text format. The object holds the
+// 'resource' and 'available' vectors, the 'max' and
+// 'allocation' matrices, and optionally 'names' (with
+// 'resources' and 'processes' lists), 'requests' (the
+// Request matrix), 'events' (a list of requests and
+// releases) and the 'processes' and 'resource_types'
+// counts. Without the counts, the
+// dimensions are taken from the resource vector and the
+// max matrix.
+//
+// Return Value
+// ------------
+// Result<ParsedInput, ParseError>   everything read from the file, or the first error encountered
+//
+// Function Parameters
+// -------------------
+// text        &str   reference  text of the file
+// file_name   &str   reference  name of the file (used in errors)
+//
+// Local Variables
+// ---------------
+// document        JsonNode            the whole file
+// num_processes   Option<usize>       declared number of processes, if given
+// num_resources   Option<usize>       declared number of resource types, if given
+// resource_names  Option<NameList>    names of the resource types, if given
+// process_names   Option<NameList>    names of the processes, if given
+// resource        Option<Row>         resource vector, once read
+// available       Option<Row>         available vector, once read
+// max             Option<MatrixRows>  max matrix, once read
+// allocation      Option<MatrixRows>  allocation matrix, once read
+// requests        Option<MatrixRows>  request matrix, if given
+// event_nodes     &[JsonNode]         events, read once the process names are known
+// names           Names               names used to look up the process of each event
+// missing         closure             error for a required key that was not given
+//
+//*********************************************************
+pub fn read_json_input(text: &str, file_name: &str) -> Result<ParsedInput, ParseError> {
+    let document = parse_json(text, file_name)?;
+    let mut num_processes = None;
+    let mut num_resources = None;
+    let mut resource_names = None;
+    let mut process_names = None;
+    let mut resource = None;
+    let mut available = None;
+    let mut max = None;
+    let mut allocation = None;
+    let mut requests = None;
+    let mut event_nodes: &[JsonNode] = &[];
+
+    for (key, value) in json_members(&document)? {
+        match key.as_str() {
+            "processes" => num_processes = Some(json_count(value)?),
+            "resource_types" => num_resources = Some(json_count(value)?),
+            "resource" => resource = Some(json_row(value)?),
+            "available" => available = Some(json_row(value)?),
+            "max" => max = Some(json_matrix(value)?),
+            "allocation" => allocation = Some(json_matrix(value)?),
+            "requests" => requests = Some(json_matrix(value)?),
+            "names" => {
+                for (key, value) in json_members(value)? {
+                    match key.as_str() {
+                        "resources" => resource_names = Some(json_names(value)?),
+                        "processes" => process_names = Some(json_names(value)?),
+                        _ => {
+                            return Err(ParseError::InvalidValue {
+                                position: value.position.clone(),
+                                token: key.clone(),
+                                expected: "'resources' or 'processes'".to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            "events" => {
+                event_nodes = match &value.kind {
+                    NodeKind::Array(elements) => elements,
+                    _ => return Err(value.mismatch("an array of events")),
+                };
+            }
+            _ => {
+                return Err(ParseError::InvalidValue {
+                    position: value.position.clone(),
+                    token: key.clone(),
+                    expected: "'resource', 'available', 'max', 'allocation', 'requests', 'names', \
+                               'events', 'processes' or 'resource_types'".to_string(),
+                });
+            }
+        }
+    }
+
+    let missing = |key: &str| ParseError::MissingKey { position: document.position.clone(), key: key.to_string() };
+    let resource = resource.ok_or_else(|| missing("resource"))?;
+    let available = available.ok_or_else(|| missing("available"))?;
+    let max: MatrixRows = max.ok_or_else(|| missing("max"))?;
+    let allocation = allocation.ok_or_else(|| missing("allocation"))?;
+
+    let names = Names {
+        resources: None,
+        processes: process_names.as_ref().map(|list| list.names.clone()),
+    };
+    let events = event_nodes.iter()
+        .map(|node| json_event(node, &names))
+        .collect::<Result<Vec<EventRow>, ParseError>>()?;
+
+    Ok(ParsedInput {
+        num_processes: num_processes.unwrap_or(max.rows.len()),
+        num_resources: num_resources.unwrap_or(resource.values.len()),
+        resource_names,
+        process_names,
+        resource,
+        available,
+        max,
+        allocation,
+        requests,
+        events,
+    })
+}
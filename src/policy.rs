@@ -0,0 +1,114 @@
+//*********************************************************
+//
+// Selection Policies
+//
+// Which process the safety algorithm lets finish next when
+// several can. Any choice gives the same verdict, since
+// letting a process finish only adds to the work vector,
+// but the choice decides the safe sequence reported, so a
+// policy turns the safe sequence into a recommended
+// execution order.
+//
+//*********************************************************
+use std::cmp::Reverse;
+
+use crate::state::{BankerState, ShapeError};
+
+//*********************************************************
+//
+// SelectionPolicy Enum
+//
+// How the safety algorithm picks the next process to
+// finish among those whose need can be met. Ties go to
+// the lowest process ID.
+//
+// Variants
+// --------
+// LowestIndex         the lowest process ID (the classic algorithm)
+// SmallestNeed        the smallest remaining need, summed over the resource types
+// LargestAllocation   the largest allocation, summed over the resource types, so the
+//                     most resources are released first
+// Priority            the highest priority, one per process (larger is more urgent)
+// Deadline            the earliest deadline, one per process
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SelectionPolicy {
+    #[default]
+    LowestIndex,
+    SmallestNeed,
+    LargestAllocation,
+    Priority(Vec<i32>),
+    Deadline(Vec<i32>),
+}
+
+impl SelectionPolicy {
+
+    //name of the policy, as given on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            SelectionPolicy::LowestIndex => "lowest-index",
+            SelectionPolicy::SmallestNeed => "smallest-need",
+            SelectionPolicy::LargestAllocation => "largest-allocation",
+            SelectionPolicy::Priority(_) => "priority",
+            SelectionPolicy::Deadline(_) => "deadline",
+        }
+    }
+
+    //checks that a priority or deadline policy gives one value to each of 'num_processes' processes
+    pub fn check(&self, num_processes: usize) -> Result<(), ShapeError> {
+        match self.values() {
+            Some(values) if values.len() != num_processes => {
+                Err(ShapeError { context: format!("the values of the '{}' policy", self.name()), expected: num_processes,
+                                 found: values.len() })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    //*********************************************************
+    //
+    // Select Function
+    //
+    // Picks the process to finish next. The policy must have
+    // passed 'check' for the state.
+    //
+    // Return Value
+    // ------------
+    // usize   process ID of the chosen process
+    //
+    // Function Parameters
+    // -------------------
+    // state        BankerState   reference  state being checked
+    // candidates   Vec<usize>    reference  processes whose need can be met, in increasing
+    //                                       order (never empty)
+    //
+    // Local Variables
+    // ---------------
+    // total   closure   sum of a row of a matrix (in 64 bits, as the sum of 32-bit values can overflow them)
+    //
+    //*********************************************************
+    pub fn select(&self, state: &BankerState, candidates: &[usize]) -> usize {
+        let total = |row: &[i32]| row.iter().map(|value| *value as i64).sum::<i64>();
+
+        //min_by_key keeps the first of equal keys, which is the lowest process ID
+        let chosen = match self {
+            SelectionPolicy::LowestIndex => candidates.first(),
+            SelectionPolicy::SmallestNeed => candidates.iter().min_by_key(|i| total(&state.need_matrix()[**i])),
+            SelectionPolicy::LargestAllocation => {
+                candidates.iter().min_by_key(|i| Reverse(total(&state.allocation_matrix()[**i])))
+            }
+            SelectionPolicy::Priority(priorities) => candidates.iter().min_by_key(|i| Reverse(priorities[**i])),
+            SelectionPolicy::Deadline(deadlines) => candidates.iter().min_by_key(|i| deadlines[**i]),
+        };
+        *chosen.expect("there is always a process to choose from")
+    }
+
+    //the values given per process (priorities or deadlines), if the policy has any
+    pub fn values(&self) -> Option<&[i32]> {
+        match self {
+            SelectionPolicy::Priority(values) | SelectionPolicy::Deadline(values) => Some(values),
+            _ => None,
+        }
+    }
+}
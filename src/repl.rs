@@ -0,0 +1,337 @@
+//*********************************************************
+//
+// Interactive Session
+//
+// A read-eval-print loop for exploring a state: commands
+// are read one line at a time, and the state they change
+// is kept in memory between commands, with every change
+// recorded so it can be undone. Commands:
+//
+//   show                    print the vectors and matrices
+//   need [<process>]        print the need matrix, or one process's need
+//   request <process> <v>   request resources for a process
+//   release <process> <v>   release resources held by a process
+//   safe?                   tell whether the state is safe
+//   sequence                print a safe sequence, or why there is none
+//   trace                   print each step of the safety algorithm
+//   undo                    undo the last granted request or release
+//   save <file>             write the state to a file in the text format
+//   help                    list the commands
+//   quit                    end the session (as does the end of the input)
+//
+//*********************************************************
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+
+use crate::display::{print_matrix, print_safe_sequence, print_state, print_trace, print_unsafe_report, print_vector, TableStyle};
+use crate::event::{Event, EventKind};
+use crate::policy::SelectionPolicy;
+use crate::serialize::write_input;
+use crate::state::{BankerState, Safety, ShapeError};
+
+//the commands of the session, as listed by 'help'
+const COMMANDS: &str = "\
+Commands:
+  show                         print the vectors and matrices
+  need [<process>]             print the need matrix, or the need of one process
+  request <process> <values>   request resources (one value per resource type)
+  release <process> <values>   release resources (one value per resource type)
+  safe?                        tell whether the state is safe
+  sequence                     print a safe sequence, or why there is none
+  trace                        print each step of the safety algorithm (the Work/Finish table)
+  undo                         undo the last granted request or release
+  save <file>                  write the state to <file> in the text format
+  help                         list the commands
+  quit                         end the session";
+
+//*********************************************************
+//
+// Repl Struct
+//
+// The state of an interactive session.
+//
+// Fields
+// ------
+// state     BankerState                  current state of the system
+// history   Vec<(String, BankerState)>   each granted command, with the state before it
+// style     TableStyle                   plain or bordered tables
+// policy    SelectionPolicy              how 'sequence' and 'trace' pick the next process to finish
+//
+//*********************************************************
+#[derive(Debug, Clone)]
+pub struct Repl {
+    state: BankerState,
+    history: Vec<(String, BankerState)>,
+    style: TableStyle,
+    policy: SelectionPolicy,
+}
+
+impl Repl {
+
+    //starts a session on 'state'
+    pub fn new(state: BankerState, style: TableStyle) -> Repl {
+        Repl { state, history: vec![], style, policy: SelectionPolicy::LowestIndex }
+    }
+
+    //sets how 'sequence' and 'trace' pick the next process to finish, if it gives a value to every process
+    pub fn with_policy(mut self, policy: SelectionPolicy) -> Result<Repl, ShapeError> {
+        policy.check(self.state.num_processes())?;
+        self.policy = policy;
+        Ok(self)
+    }
+
+    //current state of the session
+    pub fn state(&self) -> &BankerState {
+        &self.state
+    }
+
+    //the process ID 'token' refers to, or a message saying why it refers to none
+    fn find_process(&self, token: &str) -> Result<usize, String> {
+        match self.state.names().find_process(token) {
+            Some(process_id) if process_id < self.state.num_processes() => Ok(process_id),
+            _ => Err(format!("'{}' is not a process name or id", token)),
+        }
+    }
+
+    //*********************************************************
+    //
+    // Parse Event Function
+    //
+    // Reads the process and values of a 'request' or
+    // 'release' command.
+    //
+    // Return Value
+    // ------------
+    // Result<Event, String>   the event, or a message saying what is wrong
+    //
+    // Function Parameters
+    // -------------------
+    // kind    EventKind   value      request or release
+    // args    Vec<&str>   reference  arguments of the command
+    //
+    // Local Variables
+    // ---------------
+    // process_id   usize      process ID of the requesting/releasing process
+    // vector       Vec<i32>   instances of each resource type requested/released
+    //
+    //*********************************************************
+    fn parse_event(&self, kind: EventKind, args: &[&str]) -> Result<Event, String> {
+        let (process, values) = args.split_first()
+            .ok_or_else(|| format!("usage: {} <process> <values>", kind.name().to_lowercase()))?;
+        let process_id = self.find_process(process)?;
+
+        let vector = values.iter()
+            .map(|value| value.parse().map_err(|_| format!("'{}' is not an integer", value)))
+            .collect::<Result<Vec<i32>, String>>()?;
+        if vector.len() != self.state.num_resources() {
+            return Err(format!("expected {} values, one per resource type, found {}", self.state.num_resources(), vector.len()));
+        }
+        Ok(Event { kind, process_id, vector })
+    }
+
+    //*********************************************************
+    //
+    // Execute Function
+    //
+    // Runs one command line of the session, printing its
+    // results. Mistakes in the command (an unknown command,
+    // process or file, or the wrong number of values) are
+    // printed as errors and leave the state unchanged.
+    //
+    // Return Value
+    // ------------
+    // io::Result<bool>   false if the session should end, or the error encountered while writing
+    //
+    // Function Parameters
+    // -------------------
+    // line    &str        reference  command line to run
+    // out     dyn Write   reference  where to print the results
+    //
+    // Local Variables
+    // ---------------
+    // words     Vec<&str>            the command and its arguments
+    // result    Result<(), String>   a message saying what is wrong with the command, if anything
+    //
+    //*********************************************************
+    pub fn execute(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((command, args)) = words.split_first() else {
+            return Ok(true);
+        };
+
+        let result: Result<(), String> = match (*command, args) {
+            ("quit" | "exit", []) => return Ok(false),
+            ("help", []) => {
+                writeln!(out, "{}", COMMANDS)?;
+                Ok(())
+            }
+            ("show", []) => {
+                print_state(out, &self.state, self.style)?;
+                Ok(())
+            }
+            ("need", []) => {
+                let names = self.state.names();
+                print_matrix(out, self.state.need_matrix(), "Need", &names.resource_labels(self.state.num_resources()),
+                             &names.process_rows(self.state.num_processes()), self.style)?;
+                Ok(())
+            }
+            ("need", [process]) => match self.find_process(process) {
+                Ok(process_id) => {
+                    let names = self.state.names();
+                    print_vector(out, &self.state.need_matrix()[process_id], "Need", &names.resource_labels(self.state.num_resources()),
+                                 Some(&names.process_row(process_id)), self.style)?;
+                    Ok(())
+                }
+                Err(message) => Err(message),
+            },
+            ("request", _) => self.apply(EventKind::Request, args, line.trim(), out)?,
+            ("release", _) => self.apply(EventKind::Release, args, line.trim(), out)?,
+            ("safe?" | "safe", []) => {
+                match self.state.is_safe_state() {
+                    true => writeln!(out, "THE SYSTEM IS IN A SAFE STATE.")?,
+                    false => writeln!(out, "THE SYSTEM IS NOT IN A SAFE STATE.")?,
+                }
+                Ok(())
+            }
+            ("sequence", []) => {
+                match self.state.check_safety_with(&self.policy).expect("the policy was checked by 'with_policy'") {
+                    Safety::Safe(sequence) => print_safe_sequence(out, &sequence, self.state.names())?,
+                    Safety::Unsafe(report) => {
+                        writeln!(out, "THE SYSTEM IS NOT IN A SAFE STATE.")?;
+                        print_unsafe_report(out, &report, self.state.names(), self.style)?;
+                    }
+                }
+                Ok(())
+            }
+            ("trace", []) => {
+                print_trace(out, &self.state.trace_safety_with(&self.policy).expect("the policy was checked by 'with_policy'").1, self.state.names(), self.style)?;
+                Ok(())
+            }
+            ("undo", []) => match self.history.pop() {
+                Some((command, state)) => {
+                    self.state = state;
+                    writeln!(out, "Undid '{}'.", command)?;
+                    Ok(())
+                }
+                None => Err("there is nothing to undo".to_string()),
+            },
+            ("save", [path]) => match self.save(path) {
+                Ok(()) => {
+                    writeln!(out, "Saved the state to '{}'.", path)?;
+                    Ok(())
+                }
+                Err(message) => Err(message),
+            },
+            ("help" | "show" | "need" | "safe?" | "safe" | "sequence" | "trace" | "undo" | "save" | "quit" | "exit", _) => {
+                Err(format!("wrong arguments for '{}' (type 'help' for the commands)", command))
+            }
+            _ => Err(format!("unknown command '{}' (type 'help' for the commands)", command)),
+        };
+
+        if let Err(message) = result {
+            writeln!(out, "error: {}", message)?;
+        }
+        Ok(true)
+    }
+
+    //*********************************************************
+    //
+    // Apply Function
+    //
+    // Runs a 'request' or 'release' command: applies the
+    // event to the state and prints whether it was granted,
+    // and if not, why. A granted event is recorded so it
+    // can be undone.
+    //
+    // Return Value
+    // ------------
+    // io::Result<Result<(), String>>   a message if the command is wrong, or the error
+    //                                  encountered while writing
+    //
+    // Function Parameters
+    // -------------------
+    // kind      EventKind   value      request or release
+    // args      Vec<&str>   reference  arguments of the command
+    // command   &str        reference  the command line, as recorded for 'undo'
+    // out       dyn Write   reference  where to print the results
+    //
+    // Local Variables
+    // ---------------
+    // event      Event          the request or release
+    // previous   BankerState    the state before the event
+    // outcome    EventOutcome   whether the event was granted, and if not, why
+    //
+    //*********************************************************
+    fn apply(&mut self, kind: EventKind, args: &[&str], command: &str, out: &mut dyn Write) -> io::Result<Result<(), String>> {
+        let event = match self.parse_event(kind, args) {
+            Ok(event) => event,
+            Err(message) => return Ok(Err(message)),
+        };
+
+        let previous = self.state.clone();
+        let outcome = self.state.apply_event(&event);
+        let name = kind.name().to_uppercase();
+        if outcome.is_granted() {
+            self.history.push((command.to_string(), previous));
+            writeln!(out, "THE {} CAN BE GRANTED.", name)?;
+        }
+        else {
+            writeln!(out, "THE {} CANNOT BE GRANTED.", name)?;
+            if let Some(reason) = outcome.reason(self.state.names()) {
+                writeln!(out, "Reason: {}", reason)?;
+            }
+        }
+        Ok(Ok(()))
+    }
+
+    //writes the state to 'path' in the text format
+    fn save(&self, path: &str) -> Result<(), String> {
+        let describe = |error: io::Error| format!("cannot write '{}': {}", path, error);
+        let mut file = BufWriter::new(File::create(path).map_err(describe)?);
+        write_input(&mut file, &self.state, &[]).and_then(|_| file.flush()).map_err(describe)
+    }
+}
+
+//*********************************************************
+//
+// Run Repl Function
+//
+// Runs commands read from 'input', one per line, until
+// 'quit' or the end of the input.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while reading or writing, if any
+//
+// Function Parameters
+// -------------------
+// repl     Repl          reference  the session
+// input    dyn BufRead   reference  where to read commands from
+// out      dyn Write     reference  where to print the results
+// prompt   Option<&str>  value      prompt to print before each command, if any
+//
+// Local Variables
+// ---------------
+// line     String   current command line
+//
+//*********************************************************
+pub fn run_repl(repl: &mut Repl, input: &mut dyn BufRead, out: &mut dyn Write, prompt: Option<&str>) -> io::Result<()> {
+    loop {
+        if let Some(prompt) = prompt {
+            write!(out, "{}", prompt)?;
+            out.flush()?;
+        }
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            //end the prompt's line when the input ends
+            if prompt.is_some() {
+                writeln!(out)?;
+            }
+            return Ok(());
+        }
+        if !repl.execute(&line, out)? {
+            return Ok(());
+        }
+    }
+}
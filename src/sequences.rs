@@ -0,0 +1,229 @@
+//*********************************************************
+//
+// Safe Sequences
+//
+// The safety algorithm stops at the first safe sequence,
+// found by always letting the lowest-numbered process that
+// can finish go next. These functions find every safe
+// sequence instead, by backtracking, or count them without
+// listing them, with a dynamic program over the sets of
+// finished processes. The number of safe sequences tells
+// how robust a safe state is: how many orders the
+// processes can finish in, not just whether one exists.
+//
+//*********************************************************
+use crate::state::BankerState;
+
+//the most processes 'count_safe_sequences' handles; the largest sets
+//of finished processes it tracks at once number C(25, 12) = 5200300
+pub const MAX_COUNTED_PROCESSES: usize = 25;
+
+//whether the need of 'process' can be met by 'work_vec'
+fn can_finish(state: &BankerState, process: usize, work_vec: &[i32]) -> bool {
+    state.need_matrix()[process].iter().zip(work_vec).all(|(need, work)| need <= work)
+}
+
+//*********************************************************
+//
+// Extend Sequence Function
+//
+// Backtracking step of 'safe_sequences': tries every
+// process that can finish next after 'sequence', and
+// recurses with its allocation released, collecting every
+// complete sequence.
+//
+// Return Value
+// ------------
+// bool   false once more than 'limit' sequences have been found, to stop the search
+//
+// Function Parameters
+// -------------------
+// state        BankerState        reference  state whose sequences are enumerated
+// work_vec     Vec<i32>           reference  resources available after 'sequence' has finished
+// finish_vec   Vec<bool>          reference  which processes are in 'sequence'
+// sequence     Vec<usize>         reference  the partial sequence so far
+// limit        usize              value      most sequences to collect
+// found        Vec<Vec<usize>>    reference  complete sequences found so far
+//
+// Local Variables
+// ---------------
+// i            usize              loop counter for processes
+// allocation   Vec<i32>           resources allocated to process i
+//
+//*********************************************************
+fn extend_sequence(state: &BankerState, work_vec: &mut [i32], finish_vec: &mut [bool], sequence: &mut Vec<usize>,
+                   limit: usize, found: &mut Vec<Vec<usize>>) -> bool {
+    if sequence.len() == state.num_processes() {
+        if found.len() == limit {
+            return false;
+        }
+        found.push(sequence.clone());
+        return true;
+    }
+
+    for i in 0..state.num_processes() {
+        if finish_vec[i] || !can_finish(state, i, work_vec) {
+            continue;
+        }
+
+        //let process i finish, search onwards, then take it back
+        let allocation = &state.allocation_matrix()[i];
+        for (work, allocated) in work_vec.iter_mut().zip(allocation) {
+            *work += allocated;
+        }
+        finish_vec[i] = true;
+        sequence.push(i);
+
+        let keep_going = extend_sequence(state, work_vec, finish_vec, sequence, limit, found);
+
+        sequence.pop();
+        finish_vec[i] = false;
+        for (work, allocated) in work_vec.iter_mut().zip(allocation) {
+            *work -= allocated;
+        }
+        if !keep_going {
+            return false;
+        }
+    }
+    true
+}
+
+impl BankerState {
+
+    //*********************************************************
+    //
+    // Safe Sequences Function
+    //
+    // Lists the safe sequences of the state, in lexicographic
+    // order, by backtracking: at each step every process
+    // that can finish is tried in turn. Stops after 'limit'
+    // sequences, since a state with many processes can have
+    // a huge number of them.
+    //
+    // Return Value (both returned as a tuple)
+    // ------------
+    // Vec<Vec<usize>>   the first 'limit' safe sequences (empty if the state is unsafe)
+    // bool              true if these are all of the safe sequences
+    //
+    // Function Parameters
+    // -------------------
+    // limit   usize   value   most sequences to list
+    //
+    // Local Variables
+    // ---------------
+    // found      Vec<Vec<usize>>   safe sequences found so far
+    // complete   bool              whether the search ran to the end
+    //
+    //*********************************************************
+    pub fn safe_sequences(&self, limit: usize) -> (Vec<Vec<usize>>, bool) {
+        let mut found = vec![];
+        let complete = extend_sequence(self, &mut self.available_vec().to_vec(), &mut vec![false; self.num_processes()],
+                                       &mut vec![], limit, &mut found);
+        (found, complete)
+    }
+
+    //*********************************************************
+    //
+    // Count Safe Sequences Function
+    //
+    // Counts the safe sequences of the state without listing
+    // them. Which processes can finish next depends only on
+    // the set of processes that have already finished (the
+    // work vector is the available vector plus their
+    // allocations), so the number of orders in which each set
+    // can finish is a sum over the sets one process smaller.
+    // The sets are handled by size, keeping two sizes at a
+    // time, each in an array indexed by the set's rank in the
+    // combinatorial number system: the set of processes
+    // p0 < p1 < ... has rank C(p0, 1) + C(p1, 2) + ..., and
+    // the sets of one size in increasing order as bitmasks
+    // have consecutive ranks.
+    //
+    // Return Value
+    // ------------
+    // Option<u128>   the number of safe sequences (0 if the state is unsafe),
+    //                or None with more than MAX_COUNTED_PROCESSES processes
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // n          usize              number of processes
+    // binomial   Vec<Vec<usize>>    binomial coefficients C(p, j) for p, j <= n
+    // counts     Vec<u128>          number of orders in which each set of size k can finish
+    // next       Vec<u128>          the same for the sets of size k + 1
+    // mask       u32                current set of size k, as a bitmask
+    // rank       usize              rank of the current set
+    // work_vec   Vec<i32>           resources available once the set has finished
+    // members    Vec<usize>         processes in the set, in increasing order
+    // low        Vec<usize>         rank contribution of the first t members
+    // high       Vec<usize>         rank contribution of the other members, each moved up one place
+    // t          usize              number of members below the process added
+    //
+    //*********************************************************
+    pub fn count_safe_sequences(&self) -> Option<u128> {
+        let n = self.num_processes();
+        if n > MAX_COUNTED_PROCESSES {
+            return None;
+        }
+
+        let mut binomial = vec![vec![0usize; n + 2]; n + 1];
+        for p in 0..=n {
+            binomial[p][0] = 1;
+            for j in 1..=p {
+                binomial[p][j] = binomial[p - 1][j - 1] + binomial[p - 1][j];
+            }
+        }
+
+        //the empty set of finished processes can be reached in one way
+        let mut counts = vec![1u128];
+        for k in 0..n {
+            let mut next = vec![0u128; binomial[n][k + 1]];
+
+            //visit the sets of size k in increasing order, so their ranks count up from 0
+            let mut mask: u32 = (1 << k) - 1;
+            for count in counts.iter().copied() {
+                if count > 0 {
+                    let members: Vec<usize> = (0..n).filter(|p| mask & (1 << p) != 0).collect();
+                    let mut work_vec = self.available_vec().to_vec();
+                    for p in &members {
+                        for (work, allocated) in work_vec.iter_mut().zip(&self.allocation_matrix()[*p]) {
+                            *work += allocated;
+                        }
+                    }
+
+                    let mut low = vec![0; k + 1];
+                    for (j, p) in members.iter().enumerate() {
+                        low[j + 1] = low[j] + binomial[*p][j + 1];
+                    }
+                    let mut high = vec![0; k + 1];
+                    for (j, p) in members.iter().enumerate().rev() {
+                        high[j] = high[j + 1] + binomial[*p][j + 2];
+                    }
+
+                    //every process that can finish next extends the set
+                    let mut t = 0;
+                    for i in 0..n {
+                        if mask & (1 << i) != 0 {
+                            t += 1;
+                        }
+                        else if can_finish(self, i, &work_vec) {
+                            next[low[t] + binomial[i][t + 1] + high[t]] += count;
+                        }
+                    }
+                }
+
+                //move on to the next larger set of the same size (Gosper's hack)
+                if mask != 0 {
+                    let lowest = mask & mask.wrapping_neg();
+                    let ripple = mask + lowest;
+                    mask = (((ripple ^ mask) >> 2) / lowest) | ripple;
+                }
+            }
+            counts = next;
+        }
+        Some(counts[0])
+    }
+}
@@ -0,0 +1,109 @@
+//*********************************************************
+//
+// Serialization Functions
+//
+// Writes a state, and optionally the events to apply to
+// it, back into the blank-line-separated text format read
+// by 'parse', so a state can be saved and read in again as
+// a new scenario. Reading the written text gives back the
+// same state and events.
+//
+//*********************************************************
+use std::io::{self, Write};
+
+use crate::event::{Event, EventKind};
+use crate::state::BankerState;
+
+//writes the values of a vector or matrix row, separated by single spaces
+fn write_values(out: &mut dyn Write, values: &[i32]) -> io::Result<()> {
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    writeln!(out, "{}", values.join(" "))
+}
+
+//*********************************************************
+//
+// Write Input Function
+//
+// Writes a state in the text format: the first line with
+// the number of processes and resource types, the
+// 'resources:' and 'processes:' lines if the state is
+// named, the resource and available vectors, the max and
+// allocation matrices, the request matrix after a
+// 'requests:' line if the state has one, and one line per
+// event. Requests
+// are written as '<process>:<values>' and releases as
+// 'release <process>:<values>', using the process names if
+// the processes are named. A state without resource types
+// cannot be written, as its vectors would be blank lines.
+//
+// Return Value
+// ------------
+// io::Result<()>   the error encountered while writing, or InvalidInput for a state
+//                  without resource types
+//
+// Function Parameters
+// -------------------
+// out      dyn Write     reference  where to write the input
+// state    BankerState   reference  state to write
+// events   Vec<Event>    reference  events to write after the state
+//
+// Local Variables
+// ---------------
+// names    Names         names of the resource types and processes
+// row      Vec<i32>      current row of a matrix
+// event    Event         current event
+// process  String        name or ID of the event's process ("P<id>" past the named processes)
+//
+//*********************************************************
+pub fn write_input(out: &mut dyn Write, state: &BankerState, events: &[Event]) -> io::Result<()> {
+    if state.num_resources() == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "a state without resource types cannot be written in the text format"));
+    }
+
+    let names = state.names();
+    writeln!(out, "{} {}", state.num_processes(), state.num_resources())?;
+    if let Some(resources) = &names.resources {
+        writeln!(out, "resources: {}", resources.join(" "))?;
+    }
+    if let Some(processes) = &names.processes {
+        writeln!(out, "processes: {}", processes.join(" "))?;
+    }
+
+    writeln!(out)?;
+    write_values(out, state.resource_vec())?;
+    writeln!(out)?;
+    write_values(out, state.available_vec())?;
+
+    for matrix in [state.max_matrix(), state.allocation_matrix()] {
+        writeln!(out)?;
+        for row in matrix {
+            write_values(out, row)?;
+        }
+    }
+    if let Some(request_matrix) = state.request_matrix() {
+        writeln!(out, "\nrequests:")?;
+        for row in request_matrix {
+            write_values(out, row)?;
+        }
+    }
+
+    if !events.is_empty() {
+        writeln!(out)?;
+    }
+    for event in events {
+        let process = names.process_row(event.process_id);
+        match event.kind {
+            EventKind::Request => write!(out, "{}:", process)?,
+            EventKind::Release => write!(out, "release {}:", process)?,
+        }
+        write_values(out, &event.vector)?;
+    }
+    Ok(())
+}
+
+//the text of 'write_input', as a string (an error only for a state without resource types)
+pub fn to_text(state: &BankerState, events: &[Event]) -> io::Result<String> {
+    let mut text = vec![];
+    write_input(&mut text, state, events)?;
+    Ok(String::from_utf8(text).expect("the text format is valid UTF-8"))
+}
@@ -0,0 +1,1117 @@
+//*********************************************************
+//
+// Banker State
+//
+// Defines the BankerState type, which owns the resource
+// vector, the available vector and the max, allocation and
+// need matrices of a system, along with the operations of
+// the Banker's Algorithm that act on them.
+//
+//*********************************************************
+use std::error::Error;
+use std::fmt;
+
+use crate::event::{Event, EventKind};
+use crate::names::Names;
+use crate::policy::SelectionPolicy;
+
+//*********************************************************
+//
+// BankerState Struct
+//
+// The complete state of a system managed by the Banker's
+// Algorithm. Rows of the matrices are processes and
+// columns are resource types. The need matrix is always
+// kept equal to max - allocation.
+//
+// Fields
+// ------
+// resource_vec       Vec<i32>       total instances of each resource type
+// available_vec      Vec<i32>       instances of each resource type not allocated
+// max_matrix         Vec<Vec<i32>>  maximum demand of each process
+// allocation_matrix  Vec<Vec<i32>>  resources currently allocated to each process
+// need_matrix        Vec<Vec<i32>>          remaining need of each process (max - allocation)
+// names              Names                  names of the resource types and processes
+// request_matrix     Option<Vec<Vec<i32>>>  resources each process is blocked waiting for, if known
+//                                           (used by deadlock detection, not by the Banker's Algorithm;
+//                                           forgotten once a request or release changes the allocations,
+//                                           as it no longer describes what the processes wait for)
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankerState {
+    resource_vec: Vec<i32>,
+    available_vec: Vec<i32>,
+    max_matrix: Vec<Vec<i32>>,
+    allocation_matrix: Vec<Vec<i32>>,
+    need_matrix: Vec<Vec<i32>>,
+    names: Names,
+    request_matrix: Option<Vec<Vec<i32>>>,
+}
+
+//*********************************************************
+//
+// Safety Enum
+//
+// Result of running the safety algorithm on a state.
+//
+// Variants
+// --------
+// Safe(sequence)     the state is safe; 'sequence' is a safe sequence of process IDs
+// Unsafe(report)     the state is not safe; 'report' tells which processes are stuck and why
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Safety {
+    Safe(Vec<usize>),
+    Unsafe(UnsafeReport),
+}
+
+impl Safety {
+
+    //true if the checked state was safe
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Safety::Safe(_))
+    }
+}
+
+//*********************************************************
+//
+// UnsafeReport Struct
+//
+// Explains why a state is not safe: the safety algorithm
+// let some processes finish, then got stuck with a Work
+// vector that cannot meet the need of any process left.
+//
+// Fields
+// ------
+// finished   Vec<usize>      processes that could finish, in order
+// blocked    Vec<usize>      processes that could not finish
+// work       Vec<i32>        the Work vector once no more processes could finish
+// deficits   Vec<Vec<i64>>   for each blocked process, how far its need exceeds the
+//                            Work vector for each resource type (0 where it does not;
+//                            in 64 bits, as the Work vector can be negative in lenient mode)
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsafeReport {
+    pub finished: Vec<usize>,
+    pub blocked: Vec<usize>,
+    pub work: Vec<i32>,
+    pub deficits: Vec<Vec<i64>>,
+}
+
+//*********************************************************
+//
+// ResourceDeficit Struct
+//
+// How one resource type holds back the blocked processes
+// of an unsafe state.
+//
+// Fields
+// ------
+// resource    usize        column of the resource type
+// processes   Vec<usize>   blocked processes whose need of it exceeds the Work vector
+// largest     i64          the largest of their deficits
+// total       i64          the sum of their deficits
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceDeficit {
+    pub resource: usize,
+    pub processes: Vec<usize>,
+    pub largest: i64,
+    pub total: i64,
+}
+
+impl UnsafeReport {
+
+    //*********************************************************
+    //
+    // Resource Deficits Function
+    //
+    // Summarizes the deficits by resource type: for every
+    // resource type that some blocked process is short of,
+    // which processes are short of it and by how much. These
+    // are the bottleneck resources of the unsafe state.
+    //
+    // Return Value
+    // ------------
+    // Vec<ResourceDeficit>   one summary per bottleneck resource type, in column order
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // j          usize        loop counter for resources
+    // short      Vec<usize>   indexes into 'blocked' of the processes short of resource j
+    //
+    //*********************************************************
+    pub fn resource_deficits(&self) -> Vec<ResourceDeficit> {
+        (0..self.work.len())
+            .filter_map(|j| {
+                let short: Vec<usize> = (0..self.blocked.len()).filter(|k| self.deficits[*k][j] > 0).collect();
+                if short.is_empty() {
+                    return None;
+                }
+                Some(ResourceDeficit {
+                    resource: j,
+                    processes: short.iter().map(|k| self.blocked[*k]).collect(),
+                    largest: short.iter().map(|k| self.deficits[*k][j]).max().unwrap_or(0),
+                    total: short.iter().map(|k| self.deficits[*k][j]).sum(),
+                })
+            })
+            .collect()
+    }
+}
+
+//*********************************************************
+//
+// ShapeError Struct
+//
+// A list given to a state that does not have one entry
+// per process or per resource type.
+//
+// Fields
+// ------
+// context    String   what the list holds (e.g. "the process names")
+// expected   usize    number of entries the state needs
+// found      usize    number of entries given
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeError {
+    pub context: String,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: expected {} entries, found {}", self.context, self.expected, self.found)
+    }
+}
+
+impl Error for ShapeError {}
+
+//*********************************************************
+//
+// ReleaseError Enum
+//
+// The reason a release was refused.
+//
+// Variants
+// --------
+// UnknownProcess      no process has the given ID
+// WrongLength         the release vector does not have one value per resource type
+// Negative            a negative amount of a resource was released
+// ExceedsAllocation   more of a resource was released than the process holds
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseError {
+    UnknownProcess { process_id: usize },
+    WrongLength { expected: usize, found: usize },
+    Negative { resource: usize, released: i32 },
+    ExceedsAllocation { resource: usize, released: i32, held: i32 },
+}
+
+impl ReleaseError {
+
+    //describes the error using the given resource and process names
+    pub fn describe(&self, names: &Names) -> String {
+        match self {
+            ReleaseError::UnknownProcess { process_id } => format!("process {} does not exist", process_id),
+            ReleaseError::WrongLength { expected, found } => {
+                format!("expected {} values in the release, found {}", expected, found)
+            }
+            ReleaseError::Negative { resource, released } => {
+                format!("cannot release {} of resource {}", released, names.resource(*resource))
+            }
+            ReleaseError::ExceedsAllocation { resource, released, held } => {
+                format!("cannot release {} of resource {}, the process only holds {}", released, names.resource(*resource), held)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(&Names::default()))
+    }
+}
+
+impl Error for ReleaseError {}
+
+//*********************************************************
+//
+// RequestOutcome Enum
+//
+// The outcome of a resource request. A request that
+// exceeds the process's claim is a bug in the process; a
+// request that must wait or would be unsafe can be retried
+// once other processes release resources.
+//
+// Variants
+// --------
+// Granted          the request was granted
+// ExceedsClaim     the process requested more of 'resource' than its remaining need
+// MustWait         not enough of 'resource' is available right now
+// WouldBeUnsafe    granting the request would leave 'blocked_processes' unable to finish
+// UnknownProcess   no process has the given ID
+// WrongLength      the request vector does not have one value per resource type
+// Negative         a negative amount of a resource was requested
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestOutcome {
+    Granted,
+    ExceedsClaim { resource: usize, requested: i32, need: i32 },
+    MustWait { resource: usize, requested: i32, available: i32 },
+    WouldBeUnsafe { blocked_processes: Vec<usize> },
+    UnknownProcess { process_id: usize },
+    WrongLength { expected: usize, found: usize },
+    Negative { resource: usize, requested: i32 },
+}
+
+impl RequestOutcome {
+
+    //true if the request was granted
+    pub fn is_granted(&self) -> bool {
+        *self == RequestOutcome::Granted
+    }
+}
+
+impl RequestOutcome {
+
+    //describes the outcome using the given resource and process names
+    pub fn describe(&self, names: &Names) -> String {
+        match self {
+            RequestOutcome::Granted => "the request was granted".to_string(),
+            RequestOutcome::ExceedsClaim { resource, requested, need } => {
+                format!("the request for {} of resource {} exceeds the process's remaining claim of {}",
+                        requested, names.resource(*resource), need)
+            }
+            RequestOutcome::MustWait { resource, requested, available } => {
+                format!("the process must wait: {} of resource {} requested, only {} available",
+                        requested, names.resource(*resource), available)
+            }
+            RequestOutcome::WouldBeUnsafe { blocked_processes } => {
+                let processes: Vec<String> = blocked_processes.iter().map(|process| names.process(*process)).collect();
+                format!("granting the request would leave the system unsafe, blocking {}", processes.join(", "))
+            }
+            RequestOutcome::UnknownProcess { process_id } => format!("process {} does not exist", process_id),
+            RequestOutcome::WrongLength { expected, found } => {
+                format!("expected {} values in the request, found {}", expected, found)
+            }
+            RequestOutcome::Negative { resource, requested } => {
+                format!("cannot request {} of resource {}", requested, names.resource(*resource))
+            }
+        }
+    }
+}
+
+impl fmt::Display for RequestOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(&Names::default()))
+    }
+}
+
+//*********************************************************
+//
+// EventOutcome Enum
+//
+// The outcome of applying an event to a state.
+//
+// Variants
+// --------
+// Request   outcome of a request
+// Release   outcome of a release (Ok if the resources were released)
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventOutcome {
+    Request(RequestOutcome),
+    Release(Result<(), ReleaseError>),
+}
+
+impl EventOutcome {
+
+    //true if the event was granted
+    pub fn is_granted(&self) -> bool {
+        match self {
+            EventOutcome::Request(outcome) => outcome.is_granted(),
+            EventOutcome::Release(result) => result.is_ok(),
+        }
+    }
+
+    //why the event was denied, if it was, using the given resource and process names
+    pub fn reason(&self, names: &Names) -> Option<String> {
+        match self {
+            EventOutcome::Request(RequestOutcome::Granted) | EventOutcome::Release(Ok(())) => None,
+            EventOutcome::Request(outcome) => Some(outcome.describe(names)),
+            EventOutcome::Release(Err(error)) => Some(error.describe(names)),
+        }
+    }
+}
+
+impl BankerState {
+
+    //*********************************************************
+    //
+    // New Function
+    //
+    // Creates a new state from the resource vector, available
+    // vector, max matrix and allocation matrix, and computes
+    // the need matrix from them. Every matrix must have one
+    // row per process and every row one column per resource.
+    //
+    // Return Value
+    // ------------
+    // BankerState    the new state
+    //
+    // Function Parameters
+    // -------------------
+    // resource_vec       Vec<i32>       value  resource vector
+    // available_vec      Vec<i32>       value  available vector
+    // max_matrix         Vec<Vec<i32>>  value  max matrix
+    // allocation_matrix  Vec<Vec<i32>>  value  allocation matrix
+    //
+    // Local Variables
+    // ---------------
+    // state   BankerState   the state to be returned
+    //
+    //*********************************************************
+    pub fn new(resource_vec: Vec<i32>, available_vec: Vec<i32>, max_matrix: Vec<Vec<i32>>,
+                allocation_matrix: Vec<Vec<i32>>) -> BankerState {
+
+        let mut state = BankerState {
+            resource_vec,
+            available_vec,
+            max_matrix,
+            allocation_matrix,
+            need_matrix: vec![],
+            names: Names::default(),
+            request_matrix: None,
+        };
+        state.compute_need_matrix();
+        state
+    }
+
+    //*********************************************************
+    //
+    // With Names Function
+    //
+    // Gives names to the resource types and processes of the
+    // state. Each list of names, if given, must have one name
+    // per resource type or process.
+    //
+    // Return Value
+    // ------------
+    // Result<BankerState, ShapeError>   the state with the given names, or the list
+    //                                   that has the wrong number of names
+    //
+    // Function Parameters
+    // -------------------
+    // names   Names   value   names of the resource types and processes
+    //
+    // Local Variables
+    // ---------------
+    // context    &str    which list of names is being checked
+    // list       Vec<String>   the names in it
+    // expected   usize   number of names it should hold
+    //
+    //*********************************************************
+    pub fn with_names(mut self, names: Names) -> Result<BankerState, ShapeError> {
+        for (context, list, expected) in [("the resource names", &names.resources, self.num_resources()),
+                                          ("the process names", &names.processes, self.num_processes())] {
+            if let Some(list) = list.as_ref().filter(|list| list.len() != expected) {
+                return Err(ShapeError { context: context.to_string(), expected, found: list.len() });
+            }
+        }
+        self.names = names;
+        Ok(self)
+    }
+
+    //names of the resource types and processes
+    pub fn names(&self) -> &Names {
+        &self.names
+    }
+
+    //*********************************************************
+    //
+    // With Request Matrix Function
+    //
+    // Records what each process is currently blocked waiting
+    // for, for deadlock detection. The matrix must have one
+    // row per process and every row one column per resource.
+    // It describes the current allocations only, so granting
+    // a request or a release forgets it.
+    //
+    // Return Value
+    // ------------
+    // Result<BankerState, ShapeError>   the state with the given request matrix, or the
+    //                                   first part of the matrix with the wrong size
+    //
+    // Function Parameters
+    // -------------------
+    // request_matrix   Vec<Vec<i32>>   value   outstanding request of each process
+    //
+    // Local Variables
+    // ---------------
+    // i     usize      loop counter for rows
+    // row   Vec<i32>   current row of the matrix
+    //
+    //*********************************************************
+    pub fn with_request_matrix(mut self, request_matrix: Vec<Vec<i32>>) -> Result<BankerState, ShapeError> {
+        if request_matrix.len() != self.num_processes() {
+            return Err(ShapeError { context: "the rows of the Request matrix".to_string(), expected: self.num_processes(),
+                                    found: request_matrix.len() });
+        }
+        for (i, row) in request_matrix.iter().enumerate() {
+            if row.len() != self.num_resources() {
+                return Err(ShapeError { context: format!("row {} of the Request matrix", i + 1), expected: self.num_resources(),
+                                        found: row.len() });
+            }
+        }
+        self.request_matrix = Some(request_matrix);
+        Ok(self)
+    }
+
+    //outstanding request of each process, if known
+    pub fn request_matrix(&self) -> Option<&[Vec<i32>]> {
+        self.request_matrix.as_deref()
+    }
+
+    //number of processes (rows) in the system
+    pub fn num_processes(&self) -> usize {
+        self.max_matrix.len()
+    }
+
+    //number of resource types (columns) in the system
+    pub fn num_resources(&self) -> usize {
+        self.resource_vec.len()
+    }
+
+    //total instances of each resource type
+    pub fn resource_vec(&self) -> &[i32] {
+        &self.resource_vec
+    }
+
+    //instances of each resource type that are not allocated
+    pub fn available_vec(&self) -> &[i32] {
+        &self.available_vec
+    }
+
+    //maximum demand of each process
+    pub fn max_matrix(&self) -> &[Vec<i32>] {
+        &self.max_matrix
+    }
+
+    //resources currently allocated to each process
+    pub fn allocation_matrix(&self) -> &[Vec<i32>] {
+        &self.allocation_matrix
+    }
+
+    //remaining need of each process
+    pub fn need_matrix(&self) -> &[Vec<i32>] {
+        &self.need_matrix
+    }
+
+    //*********************************************************
+    //
+    // Compute Need Matrix Function
+    //
+    // Computes the need matrix from the max matrix and the
+    // allocation matrix and stores it in the state. A need
+    // that does not fit in 32 bits is clamped to the nearest
+    // value that does ('check_invariants' reports it).
+    //
+    // Return Value
+    // ------------
+    // none
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // max_row         Vec<i32>   row of the max matrix for the current process
+    // allocation_row  Vec<i32>   row of the allocation matrix for the current process
+    //
+    //*********************************************************
+    pub fn compute_need_matrix(&mut self) {
+
+        //compute the need matrix, row by row (process by process),
+        //storing the process need for each resource (max - allocation)
+        self.need_matrix = self.max_matrix.iter()
+            .zip(&self.allocation_matrix)
+            .map(|(max_row, allocation_row)| {
+                max_row.iter().zip(allocation_row).map(|(max, allocation)| max.saturating_sub(*allocation)).collect()
+            })
+            .collect();
+    }
+
+    //*********************************************************
+    //
+    // Fulfill Request Function
+    //
+    // Determines if a resource request can be fulfilled and
+    // if so, fulfills it. Nothing is changed unless the
+    // process exists and the request names a non-negative
+    // amount of every resource type. This does not check that the
+    // resulting state is safe; use 'begin_request' or
+    // 'request' to grant a request only if it is.
+    //
+    // Return Value
+    // ------------
+    // RequestOutcome    Granted if the request was fulfilled, otherwise the reason it cannot be
+    //
+    // Function Parameters
+    // -------------------
+    // process_id   usize     value      process ID of the requesting process
+    // request_vec  Vec<i32>  reference  request vector (one value per resource type)
+    //
+    // Local Variables
+    // ---------------
+    // i          usize  loop counter for resources
+    // requested  i32    requested instances of the current resource type
+    //
+    //*********************************************************
+    pub fn fulfill_request(&mut self, process_id: usize, request_vec: &[i32]) -> RequestOutcome {
+
+        //the process must exist and the request must name every resource type
+        if process_id >= self.num_processes() {
+            return RequestOutcome::UnknownProcess { process_id };
+        }
+        if request_vec.len() != self.num_resources() {
+            return RequestOutcome::WrongLength { expected: self.num_resources(), found: request_vec.len() };
+        }
+
+        //a negative request would hand resources back without the checks of a release
+        for (i, requested) in request_vec.iter().enumerate() {
+            if *requested < 0 {
+                return RequestOutcome::Negative { resource: i, requested: *requested };
+            }
+        }
+
+        //If the request exceeds the process need, this is in invalid
+        //request as the process cannot request more than it has declared
+        //it needs
+        for (i, requested) in request_vec.iter().enumerate() {
+            if *requested > self.need_matrix[process_id][i] {
+                return RequestOutcome::ExceedsClaim { resource: i, requested: *requested, need: self.need_matrix[process_id][i] };
+            }
+        }
+
+        //If the request exceeds the available resources, the system does
+        //not have enough resources to fulfill it and the process must wait
+        for (i, requested) in request_vec.iter().enumerate() {
+            if *requested > self.available_vec[i] {
+                return RequestOutcome::MustWait { resource: i, requested: *requested, available: self.available_vec[i] };
+            }
+        }
+
+        //if we have made it to this part, fulfill the request
+        //Refer to the slides detailing the Banker's Algorithm
+        //for how requests are fulfilled
+        for (i, requested) in request_vec.iter().enumerate() {
+            self.available_vec[i] -= requested;
+            self.allocation_matrix[process_id][i] += requested;
+            self.need_matrix[process_id][i] -= requested;
+        }
+        self.request_matrix = None;
+        RequestOutcome::Granted
+    }
+
+    //undoes a request fulfilled by 'fulfill_request', restoring the request matrix it forgot
+    fn undo_request(&mut self, process_id: usize, request_vec: &[i32], request_matrix: Option<Vec<Vec<i32>>>) {
+        for (i, requested) in request_vec.iter().enumerate() {
+            self.available_vec[i] += requested;
+            self.allocation_matrix[process_id][i] -= requested;
+            self.need_matrix[process_id][i] += requested;
+        }
+        self.request_matrix = request_matrix;
+    }
+
+    //*********************************************************
+    //
+    // Begin Request Function
+    //
+    // Tentatively allocates a request, if it can be fulfilled,
+    // and returns the pending request. The caller inspects the
+    // resulting state through the pending request and then
+    // commits or aborts it; a pending request that is dropped
+    // without being committed is aborted, leaving the state
+    // exactly as it was.
+    //
+    // Return Value
+    // ------------
+    // Result<PendingRequest, RequestOutcome>   the pending request, or the reason the request cannot be fulfilled
+    //
+    // Function Parameters
+    // -------------------
+    // process_id   usize     value      process ID of the requesting process
+    // request_vec  Vec<i32>  reference  request vector
+    //
+    // Local Variables
+    // ---------------
+    // request_matrix   Option<Vec<Vec<i32>>>   request matrix before the request, restored if it is aborted
+    // outcome          RequestOutcome          result of fulfilling the request
+    //
+    //*********************************************************
+    pub fn begin_request(&mut self, process_id: usize, request_vec: &[i32]) -> Result<PendingRequest<'_>, RequestOutcome> {
+        let request_matrix = self.request_matrix.take();
+        let outcome = self.fulfill_request(process_id, request_vec);
+        if outcome != RequestOutcome::Granted {
+            self.request_matrix = request_matrix;
+            return Err(outcome);
+        }
+        Ok(PendingRequest { state: self, process_id, request_vec: request_vec.to_vec(), request_matrix, finished: false })
+    }
+
+    //*********************************************************
+    //
+    // Request Function
+    //
+    // Grants a request only if it can be fulfilled and the
+    // resulting state is safe. A denied request leaves the
+    // state exactly as it was.
+    //
+    // Return Value
+    // ------------
+    // RequestOutcome    Granted, or the reason the request was denied
+    //
+    // Function Parameters
+    // -------------------
+    // process_id   usize     value      process ID of the requesting process
+    // request_vec  Vec<i32>  reference  request vector
+    //
+    // Local Variables
+    // ---------------
+    // pending   PendingRequest   the tentatively allocated request
+    // blocked   Vec<usize>       processes that could not finish after the allocation
+    //
+    //*********************************************************
+    pub fn request(&mut self, process_id: usize, request_vec: &[i32]) -> RequestOutcome {
+        let pending = match self.begin_request(process_id, request_vec) {
+            Ok(pending) => pending,
+            Err(outcome) => return outcome,
+        };
+
+        match pending.state().check_safety() {
+            Safety::Safe(_) => {
+                pending.commit();
+                RequestOutcome::Granted
+            }
+            Safety::Unsafe(report) => {
+                pending.abort();
+                RequestOutcome::WouldBeUnsafe { blocked_processes: report.blocked }
+            }
+        }
+    }
+
+    //*********************************************************
+    //
+    // Release Function
+    //
+    // Returns resources held by a process to the system,
+    // updating the available vector and the allocation and
+    // need matrices. A process cannot release more than it
+    // holds, and nothing is released if any part of the
+    // release is invalid.
+    //
+    // Return Value
+    // ------------
+    // Result<(), ReleaseError>   Ok if the resources were released, the reason if not
+    //
+    // Function Parameters
+    // -------------------
+    // process_id   usize     value      process ID of the releasing process
+    // release_vec  Vec<i32>  reference  release vector
+    //
+    // Local Variables
+    // ---------------
+    // i          usize  loop counter for resources
+    // released   i32    released instances of the current resource type
+    // held       i32    instances of the current resource type held by the process
+    //
+    //*********************************************************
+    pub fn release(&mut self, process_id: usize, release_vec: &[i32]) -> Result<(), ReleaseError> {
+
+        //the process must exist and the release must name every resource type
+        if process_id >= self.num_processes() {
+            return Err(ReleaseError::UnknownProcess { process_id });
+        }
+        if release_vec.len() != self.num_resources() {
+            return Err(ReleaseError::WrongLength { expected: self.num_resources(), found: release_vec.len() });
+        }
+
+        //the process cannot release a negative amount, or more than it holds
+        for (i, (released, held)) in release_vec.iter().zip(&self.allocation_matrix[process_id]).enumerate() {
+            if *released < 0 {
+                return Err(ReleaseError::Negative { resource: i, released: *released });
+            }
+            if released > held {
+                return Err(ReleaseError::ExceedsAllocation { resource: i, released: *released, held: *held });
+            }
+        }
+
+        //return the resources to the available vector
+        for (i, released) in release_vec.iter().enumerate() {
+            self.available_vec[i] += released;
+            self.allocation_matrix[process_id][i] -= released;
+            self.need_matrix[process_id][i] += released;
+        }
+        self.request_matrix = None;
+        Ok(())
+    }
+
+    //*********************************************************
+    //
+    // Apply Event Function
+    //
+    // Applies a request or release to the state. A request is
+    // only granted if it can be fulfilled and leaves the
+    // system in a safe state; otherwise the state is left as
+    // it was.
+    //
+    // Return Value
+    // ------------
+    // EventOutcome    the outcome of the request or release
+    //
+    // Function Parameters
+    // -------------------
+    // event   Event   reference  event to apply
+    //
+    // Local Variables
+    // ---------------
+    // none
+    //
+    //*********************************************************
+    pub fn apply_event(&mut self, event: &Event) -> EventOutcome {
+        match event.kind {
+            EventKind::Request => EventOutcome::Request(self.request(event.process_id, &event.vector)),
+            EventKind::Release => EventOutcome::Release(self.release(event.process_id, &event.vector)),
+        }
+    }
+
+    //*********************************************************
+    //
+    // Safe State Check Function
+    //
+    // Confirms whether the state is safe or not.
+    //
+    // Return Value
+    // ------------
+    // bool      true if safe state, false if not
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // none
+    //
+    //*********************************************************
+    pub fn is_safe_state(&self) -> bool {
+        self.check_safety().is_safe()
+    }
+
+    //*********************************************************
+    //
+    // Check Safety Function
+    //
+    // Runs the safety algorithm on the state and returns the
+    // safe sequence if the state is safe, or a report of the
+    // processes that got stuck and why if it is not. When
+    // several processes can finish, the lowest-numbered one
+    // goes first.
+    //
+    // Return Value
+    // ------------
+    // Safety    Safe(sequence) or Unsafe(report)
+    //
+    // Function Parameters
+    // -------------------
+    // none
+    //
+    // Local Variables
+    // ---------------
+    // none
+    //
+    //*********************************************************
+    pub fn check_safety(&self) -> Safety {
+        self.run_safety(&SelectionPolicy::LowestIndex, None)
+    }
+
+    //*********************************************************
+    //
+    // Check Safety With Function
+    //
+    // Runs the safety algorithm like 'check_safety', but lets
+    // 'policy' pick the next process to finish when several
+    // can. The verdict is the same under every policy; only
+    // the safe sequence differs. A priority or deadline
+    // policy must give one value per process.
+    //
+    // Return Value
+    // ------------
+    // Result<Safety, ShapeError>   Safe(sequence) or Unsafe(report), or the policy's
+    //                              wrong number of values
+    //
+    // Function Parameters
+    // -------------------
+    // policy   SelectionPolicy   reference  how to pick the next process to finish
+    //
+    // Local Variables
+    // ---------------
+    // none
+    //
+    //*********************************************************
+    pub fn check_safety_with(&self, policy: &SelectionPolicy) -> Result<Safety, ShapeError> {
+        policy.check(self.num_processes())?;
+        Ok(self.run_safety(policy, None))
+    }
+
+    //runs the safety algorithm like 'check_safety', recording every process examined
+    pub fn trace_safety(&self) -> (Safety, Vec<TraceStep>) {
+        let mut trace = vec![];
+        let safety = self.run_safety(&SelectionPolicy::LowestIndex, Some(&mut trace));
+        (safety, trace)
+    }
+
+    //*********************************************************
+    //
+    // Trace Safety With Function
+    //
+    // Runs the safety algorithm like 'check_safety_with', and
+    // also records every process examined along the way, so
+    // the verdict can be explained step by step.
+    //
+    // Return Value
+    // ------------
+    // Result<(Safety, Vec<TraceStep>), ShapeError>   the verdict and every process examined,
+    //                                                in order, or the policy's wrong number of values
+    //
+    // Function Parameters
+    // -------------------
+    // policy   SelectionPolicy   reference  how to pick the next process to finish
+    //
+    // Local Variables
+    // ---------------
+    // trace    Vec<TraceStep>   the steps recorded so far
+    // safety   Safety           result of the safety algorithm
+    //
+    //*********************************************************
+    pub fn trace_safety_with(&self, policy: &SelectionPolicy) -> Result<(Safety, Vec<TraceStep>), ShapeError> {
+        policy.check(self.num_processes())?;
+        let mut trace = vec![];
+        let safety = self.run_safety(policy, Some(&mut trace));
+        Ok((safety, trace))
+    }
+
+    //*********************************************************
+    //
+    // Run Safety Function
+    //
+    // The safety algorithm behind 'check_safety' and
+    // 'trace_safety': repeatedly looks for the processes
+    // whose need can be met by the work vector, lets the one
+    // the policy picks finish and adds its allocation back to
+    // the work vector, until no such process is left. Under
+    // the lowest-index policy a pass stops at the first
+    // process that can finish; the other policies look at
+    // every process left before choosing.
+    //
+    // Return Value
+    // ------------
+    // Safety    Safe(sequence) or Unsafe(report)
+    //
+    // Function Parameters
+    // -------------------
+    // policy  SelectionPolicy  reference  how to pick the next process to finish
+    // trace   Vec<TraceStep>   reference  where to record each process examined (None to not record)
+    //
+    // Local Variables
+    // ---------------
+    // work_vec          Vec<i32>       work vector, used to track available resources
+    // finish_vec        Vec<bool>      finish vector, used to track which processes have finished
+    // safe_sequence     Vec<usize>     safe sequence, used to track the safe sequence of processes
+    // pass              usize          number of the current pass over the processes
+    // candidates        Vec<usize>     processes found in this pass that can finish
+    // can_finish        bool           used to track whether an individual process can actually finish
+    // i                 usize          loop counter for processes
+    // chosen            usize          the process the policy lets finish
+    // blocked           Vec<usize>     processes that could not finish
+    // deficits          Vec<Vec<i64>>  how far the need of each blocked process exceeds the work vector
+    //
+    //*********************************************************
+    fn run_safety(&self, policy: &SelectionPolicy, mut trace: Option<&mut Vec<TraceStep>>) -> Safety {
+
+        //create work vector to keep track of available resources
+        let mut work_vec = self.available_vec.clone();
+
+        //create finish vector to keep track of which processes have finished
+        let mut finish_vec = vec![false; self.num_processes()];
+
+        //create safe sequence vector to keep track of the safe sequence of processes
+        let mut safe_sequence = vec![];
+
+        //loop until all processes that can finish have finished
+        let mut pass = 0;
+        loop {
+            pass += 1;
+
+            //look for the processes in the list that currently can finish
+            //among those that haven't yet finished
+            let mut candidates = vec![];
+            for (i, finished) in finish_vec.iter().enumerate() {
+
+                //if the process has already finished, there is nothing to check
+                if *finished {
+                    continue;
+                }
+
+                //check if the process requirements can be met with the available resources
+                let can_finish = self.need_matrix[i].iter().zip(&work_vec).all(|(need, work)| need <= work);
+
+                //record the examination of the process, if tracing
+                if let Some(trace) = trace.as_deref_mut() {
+                    trace.push(TraceStep { pass, process_id: i, need: self.need_matrix[i].clone(), work: work_vec.clone(),
+                                           can_finish, new_work: None });
+                }
+                if can_finish {
+                    candidates.push(i);
+                    if *policy == SelectionPolicy::LowestIndex {
+                        break;
+                    }
+                }
+            }
+
+            //break out of the loop once no process has been found
+            //that can finish
+            if candidates.is_empty() {
+                break;
+            }
+
+            //add the process the policy picks to the safe sequence, add
+            //its resources to the work vector and mark it as finished
+            let chosen = policy.select(self, &candidates);
+            finish_vec[chosen] = true;
+            for (work, allocation) in work_vec.iter_mut().zip(&self.allocation_matrix[chosen]) {
+                *work += allocation;
+            }
+            safe_sequence.push(chosen);
+            if let Some(trace) = trace.as_deref_mut() {
+                if let Some(step) = trace.iter_mut().rev().find(|step| step.process_id == chosen) {
+                    step.new_work = Some(work_vec.clone());
+                }
+            }
+        }
+
+        //if the safe sequence contains all of the processes, we are in a safe state
+        if safe_sequence.len() == self.num_processes() {
+            return Safety::Safe(safe_sequence);
+        }
+
+        //otherwise, the processes that never finished are blocked, each
+        //short of the resources where its need exceeds the final work vector
+        let blocked: Vec<usize> = (0..self.num_processes()).filter(|i| !finish_vec[*i]).collect();
+        let deficits = blocked.iter()
+            .map(|i| self.need_matrix[*i].iter().zip(&work_vec).map(|(need, work)| (*need as i64 - *work as i64).max(0)).collect())
+            .collect();
+        Safety::Unsafe(UnsafeReport { finished: safe_sequence, blocked, work: work_vec, deficits })
+    }
+}
+
+//*********************************************************
+//
+// TraceStep Struct
+//
+// One process examined by the safety algorithm, as
+// recorded by 'BankerState::trace_safety'.
+//
+// Fields
+// ------
+// pass         usize              number of the pass over the processes (from 1)
+// process_id   usize              process ID of the examined process
+// need         Vec<i32>           need of the process
+// work         Vec<i32>           work vector the need was compared against
+// can_finish   bool               whether the need could be met by the work vector
+// new_work     Option<Vec<i32>>   work vector after the process finished and released
+//                                 its allocation (None if it was not the one chosen to finish)
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub pass: usize,
+    pub process_id: usize,
+    pub need: Vec<i32>,
+    pub work: Vec<i32>,
+    pub can_finish: bool,
+    pub new_work: Option<Vec<i32>>,
+}
+
+//*********************************************************
+//
+// PendingRequest Struct
+//
+// A request that has been tentatively allocated by
+// 'BankerState::begin_request'. The state it borrows
+// already reflects the allocation; 'commit' keeps it and
+// 'abort' (or dropping the pending request) undoes it.
+//
+// Fields
+// ------
+// state            BankerState             state holding the tentative allocation
+// process_id       usize                   process ID of the requesting process
+// request_vec      Vec<i32>                request vector
+// request_matrix   Option<Vec<Vec<i32>>>   request matrix of the state before the request
+// finished         bool                    whether the request has been committed or aborted
+//
+//*********************************************************
+#[derive(Debug)]
+pub struct PendingRequest<'a> {
+    state: &'a mut BankerState,
+    process_id: usize,
+    request_vec: Vec<i32>,
+    request_matrix: Option<Vec<Vec<i32>>>,
+    finished: bool,
+}
+
+impl PendingRequest<'_> {
+
+    //the state with the request tentatively allocated
+    pub fn state(&self) -> &BankerState {
+        self.state
+    }
+
+    //keeps the allocation
+    pub fn commit(mut self) {
+        self.finished = true;
+    }
+
+    //undoes the allocation, restoring the state as it was before the request
+    pub fn abort(mut self) {
+        self.rollback();
+    }
+
+    //undoes the allocation unless the request has already been committed or aborted
+    fn rollback(&mut self) {
+        if !self.finished {
+            self.state.undo_request(self.process_id, &self.request_vec, self.request_matrix.take());
+            self.finished = true;
+        }
+    }
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.rollback();
+    }
+}
@@ -0,0 +1,477 @@
+//*********************************************************
+//
+// Input Validation Functions
+//
+// Checks the input read from a file against the number of
+// processes and resource types declared on its first line,
+// and checks a state for internal consistency. Every
+// violation is reported, not just the first.
+//
+//*********************************************************
+use std::fmt;
+
+use crate::error::{ParseError, Position};
+use crate::names::Names;
+use crate::parse::{MatrixRows, NameList, ParsedInput, Row};
+use crate::state::BankerState;
+
+//*********************************************************
+//
+// Check Row Function
+//
+// Checks that a row holds the expected number of values,
+// adding an error to 'errors' if it does not.
+//
+// Return Value
+// ------------
+// none
+//
+// Function Parameters
+// -------------------
+// row        Row              reference  row to check
+// expected   usize            value      number of values expected
+// context    &str             reference  what the row holds (e.g. "the Available vector")
+// errors     Vec<ParseError>  reference  errors found so far
+//
+// Local Variables
+// ---------------
+// column     usize    first extra value, or the end of the line if values are missing
+//
+//*********************************************************
+fn check_row(row: &Row, expected: usize, context: &str, errors: &mut Vec<ParseError>) {
+    if row.values.len() == expected {
+        return;
+    }
+
+    let column = match row.columns.get(expected) {
+        Some(column) => *column,
+        None => row.position.text.chars().count() + 1,
+    };
+    errors.push(ParseError::WrongCount {
+        position: Position { column, ..row.position.clone() },
+        context: context.to_string(),
+        items: "integers",
+        expected,
+        found: row.values.len(),
+    });
+}
+
+//*********************************************************
+//
+// Check Names Function
+//
+// Checks that a list of names has the expected number of
+// names and that every name is unique and can be written
+// in the text format (it cannot be empty or contain a ':',
+// a '#' or whitespace).
+//
+// Return Value
+// ------------
+// none
+//
+// Function Parameters
+// -------------------
+// list       NameList         reference  names to check
+// expected   usize            value      number of names expected
+// context    &str             reference  what the names are for (e.g. "the resource names")
+// errors     Vec<ParseError>  reference  errors found so far
+//
+// Local Variables
+// ---------------
+// i          usize     loop counter for names
+// name       String    current name
+// column     usize     column of the first extra name, or the end of the line if names are missing
+//
+//*********************************************************
+fn check_names(list: &NameList, expected: usize, context: &str, errors: &mut Vec<ParseError>) {
+    if list.names.len() != expected {
+        let column = match list.columns.get(expected) {
+            Some(column) => *column,
+            None => list.position.text.chars().count() + 1,
+        };
+        errors.push(ParseError::WrongCount {
+            position: Position { column, ..list.position.clone() },
+            context: context.to_string(),
+            items: "names",
+            expected,
+            found: list.names.len(),
+        });
+    }
+
+    for (i, name) in list.names.iter().enumerate() {
+        let position = Position { column: list.columns[i], ..list.position.clone() };
+        if name.is_empty() {
+            errors.push(ParseError::InvalidName { position, name: name.clone(), reason: "names cannot be empty".to_string() });
+        }
+        else if name.contains(':') {
+            errors.push(ParseError::InvalidName { position, name: name.clone(), reason: "names cannot contain ':'".to_string() });
+        }
+        else if name.contains('#') || name.contains(char::is_whitespace) {
+            errors.push(ParseError::InvalidName { position, name: name.clone(), reason: "names cannot contain '#' or whitespace".to_string() });
+        }
+        else if list.names[..i].contains(name) {
+            errors.push(ParseError::InvalidName { position, name: name.clone(), reason: "the name is used twice".to_string() });
+        }
+    }
+}
+
+//*********************************************************
+//
+// Check Matrix Function
+//
+// Checks that a matrix has one row per process and that
+// every row has one column per resource type.
+//
+// Return Value
+// ------------
+// none
+//
+// Function Parameters
+// -------------------
+// matrix          MatrixRows       reference  matrix to check
+// name            &str             reference  type of matrix (e.g. "Max", "Allocation")
+// num_processes   usize            value      declared number of processes
+// num_resources   usize            value      declared number of resource types
+// names           Names            reference  names of the processes, used to describe rows
+// errors          Vec<ParseError>  reference  errors found so far
+//
+// Local Variables
+// ---------------
+// i          usize     loop counter for rows
+// row        Row       current row of the matrix
+// position   Position  first extra row, or the end of the matrix if rows are missing
+//
+//*********************************************************
+fn check_matrix(matrix: &MatrixRows, name: &str, num_processes: usize, num_resources: usize,
+                names: &Names, errors: &mut Vec<ParseError>) {
+
+    if matrix.rows.len() != num_processes {
+        let position = match matrix.rows.get(num_processes) {
+            Some(row) => row.position.clone(),
+            None => matrix.end.clone(),
+        };
+        errors.push(ParseError::WrongRowCount {
+            position,
+            context: format!("the {} matrix", name),
+            expected: num_processes,
+            found: matrix.rows.len(),
+        });
+    }
+
+    //rows of named processes are described by name as well as by number
+    for (i, row) in matrix.rows.iter().enumerate() {
+        let context = match names.processes.as_ref().and_then(|processes| processes.get(i)) {
+            Some(process) => format!("row {} ({}) of the {} matrix", i + 1, process, name),
+            None => format!("row {} of the {} matrix", i + 1, name),
+        };
+        check_row(row, num_resources, &context, errors);
+    }
+}
+
+//*********************************************************
+//
+// Check Dimensions Function
+//
+// Checks that the names given (if any) match the number of
+// resource types and processes, that the resource and
+// available vectors and the vector of every event have one
+// value per resource type, and that the max and
+// allocation matrices (and the request matrix, if given)
+// have one row per process and one column per resource
+// type.
+//
+// Return Value
+// ------------
+// Vec<ParseError>   every violation found (empty if the input is valid)
+//
+// Function Parameters
+// -------------------
+// input   ParsedInput   reference  input read from the file
+//
+// Local Variables
+// ---------------
+// errors   Vec<ParseError>   violations found so far
+// names    Names             names of the resource types and processes
+//
+//*********************************************************
+pub fn check_dimensions(input: &ParsedInput) -> Vec<ParseError> {
+    let mut errors = vec![];
+    let names = input.names();
+
+    if let Some(list) = &input.resource_names {
+        check_names(list, input.num_resources, "the resource names", &mut errors);
+    }
+    if let Some(list) = &input.process_names {
+        check_names(list, input.num_processes, "the process names", &mut errors);
+    }
+
+    check_row(&input.resource, input.num_resources, "the Resource vector", &mut errors);
+    check_row(&input.available, input.num_resources, "the Available vector", &mut errors);
+    check_matrix(&input.max, "Max", input.num_processes, input.num_resources, &names, &mut errors);
+    check_matrix(&input.allocation, "Allocation", input.num_processes, input.num_resources, &names, &mut errors);
+    if let Some(requests) = &input.requests {
+        check_matrix(requests, "Request", input.num_processes, input.num_resources, &names, &mut errors);
+    }
+    for event in &input.events {
+        check_row(&event.row, input.num_resources, &format!("the {} vector", event.kind.name()), &mut errors);
+    }
+    errors
+}
+
+//*********************************************************
+//
+// Check Event Values Function
+//
+// Checks that no request or release in the input is for a
+// negative amount of a resource: a negative request would
+// hand resources back without the checks of a release,
+// and a negative release would take them.
+//
+// Return Value
+// ------------
+// Vec<ParseError>   every negative value found, at its position (empty if there are none)
+//
+// Function Parameters
+// -------------------
+// input   ParsedInput   reference  input read from the file
+//
+// Local Variables
+// ---------------
+// errors   Vec<ParseError>   negative values found so far
+// event    EventRow          current request or release
+// k        usize             loop counter for the values of the event
+// value    i32               current value
+//
+//*********************************************************
+pub fn check_event_values(input: &ParsedInput) -> Vec<ParseError> {
+    let mut errors = vec![];
+    for event in &input.events {
+        for (k, value) in event.row.values.iter().enumerate() {
+            if *value < 0 {
+                errors.push(ParseError::InvalidValue {
+                    position: Position { column: event.row.columns[k], ..event.row.position.clone() },
+                    token: value.to_string(),
+                    expected: format!("a non-negative amount in the {} vector", event.kind.name()),
+                });
+            }
+        }
+    }
+    errors
+}
+
+//*********************************************************
+//
+// CheckMode Enum
+//
+// How violations of the state invariants are treated.
+//
+// Variants
+// --------
+// Strict    violations reject the state
+// Lenient   violations are reported as warnings and the state is accepted, unless
+//           its values overflow the 32-bit integers the algorithms work in
+//
+//*********************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckMode {
+    Strict,
+    Lenient,
+}
+
+//*********************************************************
+//
+// InvariantViolation Enum
+//
+// A way in which a state is not consistent with itself.
+//
+// Variants
+// --------
+// NegativeValue          a value in the named vector or matrix is negative
+// ResourceMismatch       available plus total allocation does not equal the resource total
+// AllocationExceedsMax   a process holds more than its declared maximum (negative need)
+// MaxExceedsTotal        a process declares a maximum above what the system has in total
+// Overflow               a Need value, or the Work vector of a resource type, does not fit in 32 bits
+//
+//*********************************************************
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    NegativeValue { name: String, process: Option<usize>, resource: usize, value: i32 },
+    ResourceMismatch { resource: usize, total: i32, accounted: i64 },
+    AllocationExceedsMax { process: usize, resource: usize, allocation: i32, max: i32 },
+    MaxExceedsTotal { process: usize, resource: usize, max: i32, total: i32 },
+    Overflow { name: String, process: Option<usize>, resource: usize, value: i64 },
+}
+
+impl InvariantViolation {
+
+    //describes the violation using the given resource and process names
+    pub fn describe(&self, names: &Names) -> String {
+        match self {
+            InvariantViolation::NegativeValue { name, process: Some(process), resource, value } => {
+                format!("the {} matrix holds {} for process {}, resource {}",
+                        name, value, names.process_row(*process), names.resource(*resource))
+            }
+            InvariantViolation::NegativeValue { name, process: None, resource, value } => {
+                format!("the {} vector holds {} for resource {}", name, value, names.resource(*resource))
+            }
+            InvariantViolation::ResourceMismatch { resource, total, accounted } => {
+                format!("resource {}: Available + total Allocation is {}, but the Resource vector holds {}",
+                        names.resource(*resource), accounted, total)
+            }
+            InvariantViolation::AllocationExceedsMax { process, resource, allocation, max } => {
+                format!("process {} is allocated {} of resource {}, more than its Max of {}",
+                        names.process_row(*process), allocation, names.resource(*resource), max)
+            }
+            InvariantViolation::MaxExceedsTotal { process, resource, max, total } => {
+                format!("process {} has a Max of {} for resource {}, more than the system total of {}",
+                        names.process_row(*process), max, names.resource(*resource), total)
+            }
+            InvariantViolation::Overflow { name, process: Some(process), resource, value } => {
+                format!("the {} of process {} for resource {} is {}, which does not fit in a 32-bit integer",
+                        name, names.process_row(*process), names.resource(*resource), value)
+            }
+            InvariantViolation::Overflow { name, process: None, resource, value } => {
+                format!("the {} of resource {} can reach {}, which does not fit in a 32-bit integer",
+                        name, names.resource(*resource), value)
+            }
+        }
+    }
+
+    //whether the violation rejects the state even in lenient mode, as the algorithms cannot run on it
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, InvariantViolation::Overflow { .. })
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(&Names::default()))
+    }
+}
+
+//*********************************************************
+//
+// Check Invariants Function
+//
+// Checks that a state is consistent: every input value is
+// non-negative, every resource is accounted for (Resource
+// = Available + the sum of Allocation), no process holds
+// more than its maximum (Need is non-negative), and no
+// maximum claim exceeds the system total. It also checks
+// that every Need value, and every Work vector the
+// algorithms can reach by adding up allocations, fits in
+// 32 bits.
+//
+// Return Value
+// ------------
+// Vec<InvariantViolation>   every violation found (empty if the state is consistent)
+//
+// Function Parameters
+// -------------------
+// state   BankerState   reference  state to check
+//
+// Local Variables
+// ---------------
+// violations   Vec<InvariantViolation>   violations found so far
+// i            usize                     loop counter for processes
+// j            usize                     loop counter for resources
+// accounted    i64                       available plus total allocation of a resource (in 64 bits,
+//                                        as the sum of 32-bit values can overflow them)
+// request_matrix  Vec<Vec<i32>>          request matrix of the state (empty if it has none)
+// need         i64                       max minus allocation of a process and resource
+// highest      i64                       largest Work a resource can reach (Available plus
+//                                        every positive allocation)
+// lowest       i64                       smallest Work a resource can reach (Available plus
+//                                        every negative allocation)
+//
+//*********************************************************
+pub fn check_invariants(state: &BankerState) -> Vec<InvariantViolation> {
+    let mut violations = vec![];
+
+    //every input value must be non-negative
+    for (name, vector) in [("Resource", state.resource_vec()), ("Available", state.available_vec())] {
+        for (j, value) in vector.iter().enumerate() {
+            if *value < 0 {
+                violations.push(InvariantViolation::NegativeValue { name: name.to_string(), process: None, resource: j, value: *value });
+            }
+        }
+    }
+    let request_matrix = state.request_matrix().unwrap_or_default();
+    for (name, matrix) in [("Max", state.max_matrix()), ("Allocation", state.allocation_matrix()), ("Request", request_matrix)] {
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                if *value < 0 {
+                    violations.push(InvariantViolation::NegativeValue { name: name.to_string(), process: Some(i), resource: j, value: *value });
+                }
+            }
+        }
+    }
+
+    //every resource must be either available or allocated
+    for (j, total) in state.resource_vec().iter().enumerate() {
+        let accounted = state.available_vec()[j] as i64 + state.allocation_matrix().iter().map(|row| row[j] as i64).sum::<i64>();
+        if accounted != *total as i64 {
+            violations.push(InvariantViolation::ResourceMismatch { resource: j, total: *total, accounted });
+        }
+    }
+
+    //the Work vector must fit in 32 bits whichever processes finish
+    for j in 0..state.num_resources() {
+        let available = state.available_vec()[j] as i64;
+        let highest = available + state.allocation_matrix().iter().map(|row| (row[j] as i64).max(0)).sum::<i64>();
+        let lowest = available + state.allocation_matrix().iter().map(|row| (row[j] as i64).min(0)).sum::<i64>();
+        for value in [highest, lowest] {
+            if i32::try_from(value).is_err() {
+                violations.push(InvariantViolation::Overflow { name: "Work".to_string(), process: None, resource: j, value });
+            }
+        }
+    }
+
+    //no process may hold more than its maximum, or claim more than the system has
+    for (i, (max_row, allocation_row)) in state.max_matrix().iter().zip(state.allocation_matrix()).enumerate() {
+        for (j, (max, allocation)) in max_row.iter().zip(allocation_row).enumerate() {
+            if allocation > max {
+                violations.push(InvariantViolation::AllocationExceedsMax { process: i, resource: j, allocation: *allocation, max: *max });
+            }
+            if *max > state.resource_vec()[j] {
+                violations.push(InvariantViolation::MaxExceedsTotal { process: i, resource: j, max: *max, total: state.resource_vec()[j] });
+            }
+            let need = *max as i64 - *allocation as i64;
+            if i32::try_from(need).is_err() {
+                violations.push(InvariantViolation::Overflow { name: "Need".to_string(), process: Some(i), resource: j, value: need });
+            }
+        }
+    }
+    violations
+}
+
+//*********************************************************
+//
+// Check State Function
+//
+// Checks the invariants of a state according to the given
+// mode. In strict mode any violation rejects the state; in
+// lenient mode the violations are returned as warnings,
+// unless one of them is fatal.
+//
+// Return Value
+// ------------
+// Result<Vec<InvariantViolation>, Vec<InvariantViolation>>   warnings if accepted, violations if rejected
+//
+// Function Parameters
+// -------------------
+// state   BankerState   reference  state to check
+// mode    CheckMode     value      strict or lenient
+//
+// Local Variables
+// ---------------
+// violations   Vec<InvariantViolation>   violations found in the state
+//
+//*********************************************************
+pub fn check_state(state: &BankerState, mode: CheckMode) -> Result<Vec<InvariantViolation>, Vec<InvariantViolation>> {
+    let violations = check_invariants(state);
+    if (mode == CheckMode::Strict && !violations.is_empty()) || violations.iter().any(InvariantViolation::is_fatal) {
+        return Err(violations);
+    }
+    Ok(violations)
+}
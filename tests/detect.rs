@@ -0,0 +1,83 @@
+//*********************************************************
+//
+// Deadlock Detection Tests
+//
+// Checks the detection algorithm on the textbook example
+// in src/testfiles/example11, that matrices of the wrong
+// size are errors rather than panics, and that a state
+// forgets its Request matrix once its allocations change.
+//
+//*********************************************************
+use std::fs;
+use std::path::Path;
+
+use banker::display::{print_detection, TableStyle};
+use banker::parse::{read_input, LineReader};
+use banker::{detect_deadlock, BankerState, Detection, Names, ShapeError};
+
+//the state of src/testfiles/example11, with its Request matrix
+fn example11() -> BankerState {
+    let text = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testfiles/example11")).unwrap();
+    read_input(&mut LineReader::new(text.as_bytes(), "example11")).unwrap().into_state()
+}
+
+#[test]
+fn textbook_example_deadlocks_all_but_p0() {
+    let state = example11();
+    let detection = detect_deadlock(state.available_vec(), state.allocation_matrix(), state.request_matrix().unwrap()).unwrap();
+    assert_eq!(detection, Detection { finished: vec![0], deadlocked: vec![1, 2, 3, 4], work: vec![0, 1, 0] });
+    assert!(detection.is_deadlocked());
+
+    //without P2's extra request for C, every process can finish
+    let mut request_matrix = state.request_matrix().unwrap().to_vec();
+    request_matrix[2] = vec![0, 0, 0];
+    let detection = detect_deadlock(state.available_vec(), state.allocation_matrix(), &request_matrix).unwrap();
+    assert_eq!(detection.finished, vec![0, 2, 3, 4, 1]);
+    assert!(!detection.is_deadlocked());
+}
+
+#[test]
+fn processes_holding_nothing_cannot_be_deadlocked() {
+    let detection = detect_deadlock(&[0], &[vec![0], vec![1]], &[vec![5], vec![1]]).unwrap();
+    assert_eq!(detection, Detection { finished: vec![0], deadlocked: vec![1], work: vec![0] });
+}
+
+#[test]
+fn waiting_amounts_do_not_overflow() {
+    //a negative Available vector is only accepted in lenient mode
+    let request_matrix = vec![vec![i32::MAX, 0]];
+    let detection = detect_deadlock(&[-5, 0], &[vec![1, 1]], &request_matrix).unwrap();
+    assert_eq!(detection.deadlocked, vec![0]);
+    let mut out = vec![];
+    print_detection(&mut out, &detection, &request_matrix, &Names::default(), TableStyle::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("  P0: needs 2147483652 more of A\n"), "{}", out);
+}
+
+#[test]
+fn mismatched_matrices_are_errors() {
+    let allocation = vec![vec![0, 1], vec![1, 0]];
+    assert_eq!(detect_deadlock(&[1, 1], &allocation, &[vec![0, 0]]).unwrap_err(),
+               ShapeError { context: "the rows of the Request matrix".to_string(), expected: 2, found: 1 });
+    assert_eq!(detect_deadlock(&[1, 1], &allocation, &[vec![0, 0], vec![0]]).unwrap_err(),
+               ShapeError { context: "row 2 of the Request matrix".to_string(), expected: 2, found: 1 });
+    assert_eq!(detect_deadlock(&[1], &allocation, &[vec![0], vec![0]]).unwrap_err(),
+               ShapeError { context: "row 1 of the Allocation matrix".to_string(), expected: 1, found: 2 });
+}
+
+#[test]
+fn request_matrix_is_forgotten_when_allocations_change() {
+    let mut state = example11();
+    assert!(state.release(2, &[1, 0, 0]).is_ok());
+    assert_eq!(state.request_matrix(), None);
+
+    //a request that is denied or aborted keeps it, one that is granted forgets it
+    let mut state = example11();
+    let before = state.clone();
+    assert!(!state.request(0, &[1, 0, 0]).is_granted());
+    assert_eq!(state, before);
+    state.begin_request(1, &[0, 0, 0]).unwrap().abort();
+    assert_eq!(state, before);
+    state.begin_request(1, &[0, 0, 0]).unwrap().commit();
+    assert_eq!(state.request_matrix(), None);
+}
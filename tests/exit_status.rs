@@ -0,0 +1,92 @@
+//*********************************************************
+//
+// Exit Status Tests
+//
+// Runs the 'banker' binary on small scenarios given on
+// stdin and checks the exit code each one ends with, so
+// that scripts can rely on them.
+//
+//*********************************************************
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+//two named processes sharing 4 instances of one resource type, 2 of them available;
+//'a' needs 3 more and 'b' 1 more, so the state is safe if 'b' finishes first
+const NAMED: &str = "2 1\nprocesses: a b\n\n4\n\n2\n\n4\n2\n\n1\n1\n\n";
+
+//the same processes, unnamed, with nothing available, so neither can finish
+const UNSAFE: &str = "2 1\n\n4\n\n0\n\n3\n3\n\n2\n2\n";
+
+//runs 'banker' with 'args', writing 'input' to its stdin; returns its exit code and what it printed to stderr
+fn run(args: &[&str], input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_banker"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    //a run that ends before reading its input closes the pipe, which is not an error here
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().expect("banker exited normally"), String::from_utf8(output.stderr).unwrap())
+}
+
+#[test]
+fn unknown_processes_on_the_command_line_are_invalid_input() {
+    for process in ["7", "P2", "c"] {
+        let (code, stderr) = run(&["request", "-", process, "1"], NAMED);
+        assert_eq!(code, 6, "process {}: {}", process, stderr);
+        assert!(stderr.contains("is not a process of the state"), "{}", stderr);
+    }
+    assert_eq!(run(&["release", "-", "7", "1"], &NAMED.replace("processes: a b\n", "")).0, 6);
+    assert_eq!(run(&["request", "-", "b", "1"], NAMED).0, 0);
+}
+
+#[test]
+fn safe_states_and_granted_requests_succeed() {
+    assert_eq!(run(&["check", "-"], NAMED).0, 0);
+    assert_eq!(run(&["request", "-", "b", "1"], NAMED).0, 0);
+    assert_eq!(run(&["release", "-", "a", "1"], NAMED).0, 0);
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(run(&["check", "--bogus", "-"], NAMED).0, 1);
+    assert_eq!(run(&["request", "-", "a"], NAMED).0, 1);
+    assert_eq!(run(&["check", "--output"], NAMED).0, 1);
+}
+
+#[test]
+fn invalid_input_files() {
+    //a value that is not an integer, and a row of the wrong length
+    assert_eq!(run(&["check", "-"], &NAMED.replace("\n2\n\n4", "\nx\n\n4")).0, 6);
+    assert_eq!(run(&["check", "-"], &NAMED.replace("\n2\n\n4", "\n2 2\n\n4")).0, 6);
+
+    //an Available vector that does not add up is rejected unless '--lenient' is given
+    let inconsistent = NAMED.replace("\n2\n\n4", "\n3\n\n4");
+    let (code, stderr) = run(&["check", "-"], &inconsistent);
+    assert_eq!(code, 6);
+    assert!(stderr.contains("Available + total Allocation is 5"), "{}", stderr);
+    assert_eq!(run(&["check", "--lenient", "-"], &inconsistent).0, 0);
+
+    assert_eq!(run(&["check", "this file does not exist"], "").0, 7);
+}
+
+#[test]
+fn unsafe_states_and_denied_requests() {
+    assert_eq!(run(&["check", "-"], UNSAFE).0, 2);
+    assert_eq!(run(&["request", "-", "a", "4"], NAMED).0, 3);
+    assert_eq!(run(&["request", "-", "a", "3"], NAMED).0, 4);
+    assert_eq!(run(&["request", "-", "a", "2"], NAMED).0, 5);
+
+    //a simulation ends with the code of the first denial
+    assert_eq!(run(&["simulate", "-"], &format!("{}a: 2\nb: 4\n", NAMED)).0, 5);
+}
+
+#[test]
+fn deadlocks_are_reported() {
+    let example11 = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testfiles/example11");
+    assert_eq!(run(&["detect", example11.to_str().unwrap()], "").0, 8);
+}
@@ -0,0 +1,63 @@
+//*********************************************************
+//
+// Invariant Tests
+//
+// Checks the consistency checks on a state: that every
+// violation is reported rather than just the first, that
+// totals are taken without overflowing, and that values
+// the algorithms cannot hold in 32 bits reject the state
+// even in lenient mode.
+//
+//*********************************************************
+use banker::validate::{check_invariants, check_state, CheckMode, InvariantViolation};
+use banker::BankerState;
+
+#[test]
+fn every_invariant_violation_is_reported() {
+    let state = BankerState::new(vec![5, 3], vec![-1, 1], vec![vec![2, 9], vec![1, 1]], vec![vec![3, 1], vec![1, 1]]);
+    assert_eq!(check_invariants(&state), vec![
+        InvariantViolation::NegativeValue { name: "Available".to_string(), process: None, resource: 0, value: -1 },
+        InvariantViolation::ResourceMismatch { resource: 0, total: 5, accounted: 3 },
+        InvariantViolation::AllocationExceedsMax { process: 0, resource: 0, allocation: 3, max: 2 },
+        InvariantViolation::MaxExceedsTotal { process: 0, resource: 1, max: 9, total: 3 },
+    ]);
+}
+
+#[test]
+fn allocation_totals_do_not_overflow() {
+    let state = BankerState::new(vec![i32::MAX, 1], vec![0, 1], vec![vec![2_000_000_000, 0]; 2], vec![vec![2_000_000_000, 0]; 2]);
+    assert_eq!(check_invariants(&state), vec![
+        InvariantViolation::ResourceMismatch { resource: 0, total: i32::MAX, accounted: 4_000_000_000 },
+        InvariantViolation::Overflow { name: "Work".to_string(), process: None, resource: 0, value: 4_000_000_000 },
+    ]);
+    assert_eq!(check_invariants(&state)[0].to_string(),
+               "resource A: Available + total Allocation is 4000000000, but the Resource vector holds 2147483647");
+}
+
+#[test]
+fn need_overflow_is_a_violation_rather_than_a_panic() {
+    let state = BankerState::new(vec![i32::MAX], vec![i32::MAX], vec![vec![i32::MAX]], vec![vec![-1]]);
+    assert_eq!(state.need_matrix(), &[vec![i32::MAX]]);
+    let violations = check_state(&state, CheckMode::Lenient).unwrap_err();
+    assert!(violations.contains(&InvariantViolation::Overflow {
+        name: "Need".to_string(), process: Some(0), resource: 0, value: i32::MAX as i64 + 1,
+    }), "{:?}", violations);
+    assert_eq!(violations.iter().filter(|violation| violation.is_fatal()).count(), 1);
+}
+
+#[test]
+fn work_overflow_rejects_the_state_even_in_lenient_mode() {
+    let allocation = vec![vec![2_000_000_000, 0]; 2];
+    let state = BankerState::new(vec![i32::MAX, 1], vec![1, 1], allocation.clone(), allocation);
+    assert_eq!(check_invariants(&state), vec![
+        InvariantViolation::ResourceMismatch { resource: 0, total: i32::MAX, accounted: 4_000_000_001 },
+        InvariantViolation::Overflow { name: "Work".to_string(), process: None, resource: 0, value: 4_000_000_001 },
+    ]);
+    assert!(check_state(&state, CheckMode::Lenient).is_err());
+
+    //without the overflow, lenient mode accepts the same kind of inconsistency
+    let allocation = vec![vec![1_000_000_000, 0]; 2];
+    let state = BankerState::new(vec![i32::MAX, 1], vec![1, 1], allocation.clone(), allocation);
+    assert_eq!(check_state(&state, CheckMode::Lenient).map(|warnings| warnings.len()), Ok(1));
+    assert!(state.is_safe_state());
+}
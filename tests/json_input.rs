@@ -0,0 +1,81 @@
+//*********************************************************
+//
+// JSON Input Tests
+//
+// Checks the JSON input format: what 'input_to_json'
+// writes for a state and its events, and how the parser
+// handles the corners of the JSON grammar.
+//
+//*********************************************************
+use banker::error::ParseError;
+use banker::json::{input_to_json, parse_json, Json, NodeKind};
+use banker::parse::read_json_input;
+use banker::{BankerState, Event, EventKind, Names};
+
+//the line and column of an error
+fn line_and_column(error: &ParseError) -> (usize, usize) {
+    let position = error.position().expect("the error has a position");
+    (position.line, position.column)
+}
+
+//the value of member 'key' of 'object'
+fn member<'a>(object: &'a Json, key: &str) -> &'a Json {
+    match object {
+        Json::Object(members) => &members.iter().find(|(name, _)| name == key).expect("the member is present").1,
+        _ => panic!("expected an object, found {}", object),
+    }
+}
+
+#[test]
+fn events_past_the_named_processes_are_written_by_id() {
+    let names = Names { resources: None, processes: Some(vec!["a".to_string(), "b".to_string()]) };
+    let state = BankerState::new(vec![4], vec![2], vec![vec![2], vec![2]], vec![vec![1], vec![1]]).with_names(names).unwrap();
+    let events = vec![
+        Event { kind: EventKind::Request, process_id: 1, vector: vec![1] },
+        Event { kind: EventKind::Release, process_id: 7, vector: vec![1] },
+    ];
+    let document = input_to_json(&state, &events);
+    let processes: Vec<&Json> = match member(&document, "events") {
+        Json::Array(events) => events.iter().map(|event| member(event, "process")).collect(),
+        other => panic!("expected an array of events, found {}", other),
+    };
+    assert_eq!(processes, vec![&Json::String("b".to_string()), &Json::Number(7)]);
+}
+
+#[test]
+fn deeply_nested_json_is_an_error() {
+    let error = parse_json(&"[".repeat(200_000), "deep.json").unwrap_err();
+    assert_eq!(error.message(), "expected a value nested at most 64 arrays or objects deep, found '['");
+    assert_eq!(line_and_column(&error), (1, 65));
+    assert!(parse_json(&format!("{}{}", "[".repeat(64), "]".repeat(64)), "deep.json").is_ok());
+}
+
+#[test]
+fn json_surrogate_pairs_are_one_character() {
+    //as written by Python's json.dumps
+    let text = r#"{"names": {"processes": ["\ud83d\ude00", "b"]}, "resource": [2], "available": [1], "max": [[1], [1]], "allocation": [[0], [1]]}"#;
+    let input = read_json_input(text, "test.json").unwrap();
+    assert_eq!(input.names().processes, Some(vec!["😀".to_string(), "b".to_string()]));
+
+    for (escape, column) in [(r#""\ud83d""#, 2), (r#""\ud83dx""#, 2), (r#""\ude00""#, 2), (r#""a\ud83dA""#, 3)] {
+        let error = parse_json(escape, "test.json").unwrap_err();
+        assert_eq!(line_and_column(&error), (1, column), "{}", escape);
+    }
+}
+
+#[test]
+fn numbers_follow_the_json_grammar() {
+    for (text, value) in [("0", 0), ("-0", 0), ("7", 7), ("-12", -12), ("100", 100)] {
+        assert_eq!(parse_json(text, "test.json").unwrap().kind, NodeKind::Number(value), "{}", text);
+    }
+
+    //a '+' sign and leading zeros are not JSON, wherever they appear
+    for (text, token, column) in [("[1, +5]", "+5", 5), ("[-+5]", "-+5", 2), ("[007]", "007", 2), ("[1, -01]", "-01", 5), ("00", "00", 1)] {
+        match parse_json(text, "test.json") {
+            Err(ParseError::InvalidValue { position, token: found, .. }) => {
+                assert_eq!((position.column, found.as_str()), (column, token), "{}", text);
+            }
+            other => panic!("expected an invalid number in {}, got {:?}", text, other),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//*********************************************************
+//
+// JSON Output Tests
+//
+// Runs the 'banker' binary with '--format json' and reads
+// the document it prints back with the JSON parser,
+// checking its keys and values for a safe state, an
+// unsafe state and a denied request, and the count of the
+// safe sequences of an unsafe state.
+//
+//*********************************************************
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use banker::json::{parse_json, JsonNode, NodeKind};
+
+//two named processes sharing 4 instances of one resource type, 2 of them available;
+//'a' needs 3 more and 'b' 1 more, so the state is safe if 'b' finishes first
+const SAFE: &str = "2 1\nprocesses: a b\n\n4\n\n2\n\n4\n2\n\n1\n1\n\n";
+
+//two processes each needing 1 more instance, with none available
+const UNSAFE: &str = "2 1\n\n4\n\n0\n\n3\n3\n\n2\n2\n";
+
+//runs 'banker' with 'args' and '--format json', writing 'input' to its stdin; returns the document it printed
+fn run_json(args: &[&str], input: &str) -> JsonNode {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_banker"))
+        .args(args)
+        .args(["--format", "json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    parse_json(&String::from_utf8(output.stdout).unwrap(), "stdout").unwrap()
+}
+
+//the keys of an object, in order
+fn keys(node: &JsonNode) -> Vec<&str> {
+    match &node.kind {
+        NodeKind::Object(members) => members.iter().map(|(key, _)| key.as_str()).collect(),
+        _ => panic!("expected an object, found {}", node.description()),
+    }
+}
+
+//the value at 'path', a list of object keys and array indexes
+fn at<'a>(node: &'a JsonNode, path: &[&str]) -> &'a JsonNode {
+    path.iter().fold(node, |node, step| match &node.kind {
+        NodeKind::Object(members) => &members.iter().find(|(key, _)| key == step).unwrap_or_else(|| panic!("no '{}'", step)).1,
+        NodeKind::Array(elements) => &elements[step.parse::<usize>().unwrap()],
+        _ => panic!("cannot look up '{}' in {}", step, node.description()),
+    })
+}
+
+//the integers of an array
+fn numbers(node: &JsonNode) -> Vec<i64> {
+    match &node.kind {
+        NodeKind::Array(elements) => elements.iter().map(|element| match element.kind {
+            NodeKind::Number(value) => value,
+            _ => panic!("expected a number, found {}", element.description()),
+        }).collect(),
+        _ => panic!("expected an array, found {}", node.description()),
+    }
+}
+
+#[test]
+fn safe_state_document() {
+    let document = run_json(&["check", "-"], SAFE);
+    assert_eq!(keys(&document),
+               vec!["processes", "resource_types", "names", "initial_state", "policy", "safety", "events", "final_state"]);
+    assert_eq!(at(&document, &["processes"]).kind, NodeKind::Number(2));
+    assert_eq!(at(&document, &["names", "processes", "1"]).kind, NodeKind::String("b".to_string()));
+    assert_eq!(keys(at(&document, &["initial_state"])), vec!["resource", "available", "max", "allocation", "need"]);
+    assert_eq!(numbers(at(&document, &["initial_state", "need", "0"])), vec![3]);
+    assert_eq!(at(&document, &["policy", "name"]).kind, NodeKind::String("lowest-index".to_string()));
+    assert_eq!(keys(at(&document, &["safety"])), vec!["safe", "safe_sequence"]);
+    assert_eq!(at(&document, &["safety", "safe"]).kind, NodeKind::Bool(true));
+    assert_eq!(numbers(at(&document, &["safety", "safe_sequence"])), vec![1, 0]);
+}
+
+#[test]
+fn unsafe_state_document() {
+    let document = run_json(&["check", "-"], UNSAFE);
+    let safety = at(&document, &["safety"]);
+    assert_eq!(keys(safety), vec!["safe", "safe_sequence", "finished", "blocked", "work", "deficits", "bottlenecks"]);
+    assert_eq!(at(safety, &["safe"]).kind, NodeKind::Bool(false));
+    assert_eq!(at(safety, &["safe_sequence"]).kind, NodeKind::Null);
+    assert_eq!(numbers(at(safety, &["finished"])), vec![]);
+    assert_eq!(numbers(at(safety, &["blocked"])), vec![0, 1]);
+    assert_eq!(numbers(at(safety, &["work"])), vec![0]);
+    assert_eq!(at(safety, &["deficits", "1", "process"]).kind, NodeKind::Number(1));
+    assert_eq!(numbers(at(safety, &["deficits", "1", "deficit"])), vec![1]);
+    assert_eq!(keys(at(safety, &["bottlenecks", "0"])), vec!["resource", "processes", "largest", "total"]);
+    assert_eq!(at(safety, &["bottlenecks", "0", "total"]).kind, NodeKind::Number(2));
+}
+
+#[test]
+fn denied_request_document() {
+    let document = run_json(&["request", "-", "a", "2"], SAFE);
+    let event = at(&document, &["events", "0"]);
+    assert_eq!(keys(event), vec!["kind", "process", "vector", "decision"]);
+    assert_eq!(at(event, &["kind"]).kind, NodeKind::String("request".to_string()));
+    assert_eq!(at(event, &["process"]).kind, NodeKind::Number(0));
+    assert_eq!(numbers(at(event, &["vector"])), vec![2]);
+    assert_eq!(keys(at(event, &["decision"])), vec!["granted", "outcome", "reason"]);
+    assert_eq!(at(event, &["decision", "granted"]).kind, NodeKind::Bool(false));
+    assert_eq!(at(event, &["decision", "outcome"]).kind, NodeKind::String("would_be_unsafe".to_string()));
+    assert_eq!(at(event, &["decision", "reason"]).kind,
+               NodeKind::String("granting the request would leave the system unsafe, blocking a, b".to_string()));
+
+    //the request is not granted, so the final state is the initial one
+    assert_eq!(numbers(at(&document, &["final_state", "available"])), vec![2]);
+}
+
+#[test]
+fn unsafe_states_have_no_safe_sequences_to_count() {
+    //too many processes for the exact count, but none of them can finish
+    let input = format!("30 1\n\n30\n\n0\n\n{}\n{}", "2\n".repeat(30), "1\n".repeat(30));
+    let document = run_json(&["sequences", "--count", "-"], &input);
+    assert_eq!(at(&document, &["safety", "safe"]).kind, NodeKind::Bool(false));
+    assert_eq!(at(&document, &["count"]).kind, NodeKind::Number(0));
+
+    let document = run_json(&["sequences", "-"], &input);
+    assert_eq!(numbers(at(&document, &["safe_sequences"])), vec![]);
+    assert_eq!(at(&document, &["complete"]).kind, NodeKind::Bool(true));
+    assert_eq!(at(&document, &["count"]).kind, NodeKind::Number(0));
+}
@@ -0,0 +1,67 @@
+//*********************************************************
+//
+// Name Tests
+//
+// Checks how named processes are looked up: by name, by
+// process ID, or by ID written as in messages, and that
+// an ID past the last named process refers to none. Also
+// checks that names, like a Request matrix, must match
+// the shape of the state they are given to.
+//
+//*********************************************************
+use banker::error::ParseError;
+use banker::parse::{read_input, LineReader};
+use banker::{BankerState, Names, ShapeError};
+
+//names for processes 'a' and 'b' of unnamed resource types
+fn named() -> Names {
+    Names { resources: None, processes: Some(vec!["a".to_string(), "b".to_string()]) }
+}
+
+#[test]
+fn processes_are_found_by_name_or_id() {
+    let names = named();
+    assert_eq!(names.find_process("b"), Some(1));
+    assert_eq!(names.find_process("1"), Some(1));
+    assert_eq!(names.find_process("P0"), Some(0));
+    assert_eq!(names.find_process("c"), None);
+    assert_eq!(names.find_process("2"), None);
+    assert_eq!(names.find_process("P7"), None);
+
+    //without names, any ID is looked up, and checked against the state later
+    assert_eq!(Names::default().find_process("7"), Some(7));
+}
+
+#[test]
+fn ids_past_the_named_processes_are_labeled_by_id() {
+    let names = named();
+    assert_eq!(names.process(1), "b");
+    assert_eq!(names.process(7), "P7");
+    assert_eq!(names.process_row(7), "P7");
+    assert_eq!(Names::default().process_row(7), "7");
+}
+
+#[test]
+fn events_for_ids_past_the_named_processes_are_errors() {
+    let text = "2 1\nprocesses: a b\n\n4\n\n2\n\n2\n2\n\n1\n1\n\n7: 1\n";
+    match read_input(&mut LineReader::new(text.as_bytes(), "test")) {
+        Err(ParseError::InvalidValue { position, token, .. }) => {
+            assert_eq!((position.line, position.column, token.as_str()), (14, 1, "7"));
+        }
+        other => panic!("expected an error for process 7, got {:?}", other.map(|_| ())),
+    }
+    assert!(read_input(&mut LineReader::new(text.replace("7: 1", "1: 1").as_bytes(), "test")).is_ok());
+}
+
+#[test]
+fn mismatched_names_and_request_matrices_are_errors() {
+    let state = BankerState::new(vec![4, 4], vec![2, 2], vec![vec![2, 2]; 2], vec![vec![1, 1]; 2]);
+    let names = Names { resources: Some(vec!["cpu".to_string()]), processes: None };
+    assert_eq!(state.clone().with_names(names).unwrap_err(),
+               ShapeError { context: "the resource names".to_string(), expected: 2, found: 1 });
+    assert_eq!(state.clone().with_request_matrix(vec![vec![0, 0]]).unwrap_err().to_string(),
+               "the rows of the Request matrix: expected 2 entries, found 1");
+    assert_eq!(state.clone().with_request_matrix(vec![vec![0, 0], vec![0]]).unwrap_err().to_string(),
+               "row 2 of the Request matrix: expected 2 entries, found 1");
+    assert!(state.with_request_matrix(vec![vec![0, 0]; 2]).is_ok());
+}
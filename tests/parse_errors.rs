@@ -0,0 +1,153 @@
+//*********************************************************
+//
+// Parse Error Tests
+//
+// Checks that errors in an input file are reported at the
+// right line and column, whatever the whitespace, line
+// endings and comments around them, that the diagnostic
+// marks that column, and that the validation pass reports
+// every mismatch with the declared dimensions and every
+// negative event value rather than just the first.
+//
+//*********************************************************
+use std::io::{self, BufRead, Read};
+
+use banker::display::write_parse_error;
+use banker::error::{ParseError, Position};
+use banker::parse::{read_input, LineReader, ParsedInput};
+use banker::validate::{check_dimensions, check_event_values};
+
+//reads 'text' in the text format, as the file "test"
+fn parse(text: &str) -> Result<ParsedInput, ParseError> {
+    read_input(&mut LineReader::new(text.as_bytes(), "test"))
+}
+
+//the error reading 'text' gives
+fn parse_error(text: &str) -> ParseError {
+    match parse(text) {
+        Ok(_) => panic!("expected an error reading:\n{}", text),
+        Err(error) => error,
+    }
+}
+
+//the line and column of an error
+fn line_and_column(error: &ParseError) -> (usize, usize) {
+    let position = error.position().expect("the error has a position");
+    (position.line, position.column)
+}
+
+//the diagnostic written for an error
+fn diagnostic(error: &ParseError) -> String {
+    let mut out = vec![];
+    write_parse_error(&mut out, error).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+//a reader whose every read fails
+struct FailingReader;
+
+impl Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk on fire"))
+    }
+}
+
+impl BufRead for FailingReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Err(io::Error::other("disk on fire"))
+    }
+
+    fn consume(&mut self, _: usize) {}
+}
+
+#[test]
+fn invalid_value_is_reported_at_its_token() {
+    let error = parse_error("2 2\n\n4 4\n\n1 x\n");
+    assert_eq!(error, ParseError::InvalidValue {
+        position: Position { file: "test".to_string(), line: 5, column: 3, text: "1 x".to_string() },
+        token: "x".to_string(),
+        expected: "an integer".to_string(),
+    });
+    assert_eq!(diagnostic(&error), "\
+error: expected an integer, found 'x'
+ --> test:5:3
+  |
+5 | 1 x
+  |   ^
+");
+}
+
+#[test]
+fn caret_keeps_the_tabs_of_the_line() {
+    let error = parse_error("2 2\n\n4\t\t4\n\n1\t2 \t?\n");
+    assert_eq!(line_and_column(&error), (5, 6));
+    assert!(diagnostic(&error).ends_with("5 | 1\t2 \t?\n  |  \t  \t^\n"), "{}", diagnostic(&error));
+}
+
+#[test]
+fn first_line_errors() {
+    assert_eq!(line_and_column(&parse_error("4 3 7\n")), (1, 5));
+    assert_eq!(line_and_column(&parse_error("4\n")), (1, 2));
+    assert_eq!(line_and_column(&parse_error("  -4 3\n")), (1, 3));
+}
+
+#[test]
+fn end_of_file_is_reported_on_the_line_after_the_last() {
+    let error = parse_error("2 2\n\n4 4\n");
+    assert!(matches!(&error, ParseError::UnexpectedEof { expected, .. } if expected == "the Available vector"), "{:?}", error);
+    assert_eq!(line_and_column(&error), (4, 1));
+
+    let error = parse_error("");
+    assert!(matches!(&error, ParseError::UnexpectedEof { expected, .. } if expected == "the number of processes and resource types"));
+    assert_eq!(line_and_column(&error), (1, 1));
+}
+
+#[test]
+fn io_errors_name_the_file() {
+    let error = read_input(&mut LineReader::new(FailingReader, "broken.txt")).unwrap_err();
+    assert_eq!(error, ParseError::Io { file: "broken.txt".to_string(), message: "disk on fire".to_string() });
+    assert_eq!(error.position(), None);
+    assert_eq!(error.to_string(), "broken.txt: disk on fire");
+    assert_eq!(diagnostic(&error), "error: disk on fire\n --> broken.txt\n");
+}
+
+#[test]
+fn positions_ignore_line_endings_and_comments() {
+    //the byte order mark, the '\r' and the comments do not shift the columns
+    let text = "\u{feff}2 2 # sizes\r\n# a comment line\r\n\r\n  4  4\r\n\r\n4 4\r\n\r\n1 1\r\n2\t1 # row two\r\n\r\n0 0\r\n1 1\r\nrequest 1: 1 z # bad\r\n";
+    let error = parse_error(text);
+    assert_eq!(line_and_column(&error), (13, 14));
+    assert_eq!(error.position().unwrap().text, "request 1: 1 z # bad");
+
+    //a comment is not part of the row it follows
+    let input = parse(&text.replace("1 z", "1 0")).unwrap();
+    assert_eq!(check_dimensions(&input), vec![]);
+    assert_eq!(input.max.rows[1].values, vec![2, 1]);
+}
+
+#[test]
+fn every_dimension_mismatch_is_reported() {
+    let input = parse("3 2\nprocesses: a b c\n\n4 4 4\n\n2 2\n\n1 1\n2 2\n\n0 0\n1\n0 0\n0 0\n\na: 1 1 1\n").unwrap();
+    let errors: Vec<(String, (usize, usize))> = check_dimensions(&input).iter()
+        .map(|error| (error.message(), line_and_column(error)))
+        .collect();
+    assert_eq!(errors, vec![
+        ("the Resource vector: expected 2 integers, found 3".to_string(), (4, 5)),
+        ("the Max matrix: expected 3 rows, found 2".to_string(), (10, 1)),
+        ("the Allocation matrix: expected 3 rows, found 4".to_string(), (14, 1)),
+        ("row 2 (b) of the Allocation matrix: expected 2 integers, found 1".to_string(), (12, 2)),
+        ("the Request vector: expected 2 integers, found 3".to_string(), (16, 8)),
+    ]);
+}
+
+#[test]
+fn negative_event_values_are_reported() {
+    let input = parse("1 2\n\n4 4\n\n4 4\n\n2 2\n\n0 0\n\n0: -1 0\nrelease 0: 0 -2\n").unwrap();
+    let errors: Vec<(String, (usize, usize))> = check_event_values(&input).iter()
+        .map(|error| (error.message(), line_and_column(error)))
+        .collect();
+    assert_eq!(errors, vec![
+        ("expected a non-negative amount in the Request vector, found '-1'".to_string(), (11, 4)),
+        ("expected a non-negative amount in the Release vector, found '-2'".to_string(), (12, 14)),
+    ]);
+}
@@ -0,0 +1,111 @@
+//*********************************************************
+//
+// Selection Policy Tests
+//
+// Checks that every selection policy reaches the same
+// verdict as the classic safety algorithm, that the safe
+// sequence it reports is one of the state's safe
+// sequences, that each policy orders the processes as it
+// says, and that a policy with too few or too many values
+// is an error rather than a panic.
+//
+//*********************************************************
+use banker::display::TableStyle;
+use banker::generate::generate_state;
+use banker::repl::Repl;
+use banker::{BankerState, Safety, SelectionPolicy, ShapeError};
+
+//the safe sequence 'policy' reports for 'state', if it is safe
+fn safe_sequence(state: &BankerState, policy: &SelectionPolicy) -> Option<Vec<usize>> {
+    match state.check_safety_with(policy).unwrap() {
+        Safety::Safe(sequence) => Some(sequence),
+        Safety::Unsafe(_) => None,
+    }
+}
+
+//the policies to try on a state of 'num_processes' processes
+fn policies(num_processes: usize) -> Vec<SelectionPolicy> {
+    let values: Vec<i32> = (0..num_processes as i32).map(|i| (i * 7) % 5).collect();
+    vec![
+        SelectionPolicy::LowestIndex,
+        SelectionPolicy::SmallestNeed,
+        SelectionPolicy::LargestAllocation,
+        SelectionPolicy::Priority(values.clone()),
+        SelectionPolicy::Deadline(values),
+    ]
+}
+
+//a state of three processes that have all reached their maximum, so any can finish first
+fn any_order_state() -> BankerState {
+    let allocation = vec![vec![1, 1], vec![3, 0], vec![0, 2]];
+    BankerState::new(vec![5, 4], vec![1, 1], allocation.clone(), allocation)
+}
+
+//a state of three processes that can finish in any order, needing 4, 1 and 2 more instances
+fn differing_needs_state() -> BankerState {
+    BankerState::new(vec![6], vec![4], vec![vec![4], vec![2], vec![3]], vec![vec![0], vec![1], vec![1]])
+}
+
+#[test]
+fn policies_agree_on_the_verdict() {
+    for seed in 0..20 {
+        let state = generate_state(6, 3, seed);
+        let (sequences, _) = state.safe_sequences(usize::MAX);
+        for policy in policies(6) {
+            let sequence = safe_sequence(&state, &policy).expect("generated states are safe");
+            assert!(sequences.contains(&sequence), "seed {}, policy {}", seed, policy.name());
+        }
+    }
+
+    let state = BankerState::new(vec![3, 2], vec![1, 0], vec![vec![2, 2], vec![2, 2]], vec![vec![1, 1], vec![1, 1]]);
+    for policy in policies(2) {
+        assert_eq!(safe_sequence(&state, &policy), None);
+    }
+}
+
+#[test]
+fn policies_order_the_processes() {
+    let state = any_order_state();
+    let order = |policy: SelectionPolicy| safe_sequence(&state, &policy);
+    assert_eq!(order(SelectionPolicy::LowestIndex), Some(vec![0, 1, 2]));
+    assert_eq!(order(SelectionPolicy::LargestAllocation), Some(vec![1, 0, 2]));
+    assert_eq!(order(SelectionPolicy::Priority(vec![1, 2, 3])), Some(vec![2, 1, 0]));
+    assert_eq!(order(SelectionPolicy::Deadline(vec![5, 5, 1])), Some(vec![2, 0, 1]));
+
+    //every process can finish first, so the smallest need goes first, then the next smallest
+    let state = differing_needs_state();
+    assert_eq!(safe_sequence(&state, &SelectionPolicy::SmallestNeed), Some(vec![1, 2, 0]));
+    assert_eq!(safe_sequence(&state, &SelectionPolicy::LowestIndex), Some(vec![0, 1, 2]));
+}
+
+#[test]
+fn totals_larger_than_32_bits_are_ordered() {
+    //P0 needs i32::MAX of both resource types, P1 just 1 of the first
+    let state = BankerState::new(vec![i32::MAX; 2], vec![i32::MAX; 2], vec![vec![i32::MAX; 2], vec![1, 0]], vec![vec![0, 0]; 2]);
+    assert_eq!(safe_sequence(&state, &SelectionPolicy::SmallestNeed), Some(vec![1, 0]));
+
+    //P0 holds nearly all of both resource types, P1 the rest
+    let allocation = vec![vec![i32::MAX - 1; 2], vec![1, 1]];
+    let state = BankerState::new(vec![i32::MAX; 2], vec![0, 0], allocation.clone(), allocation);
+    assert_eq!(safe_sequence(&state, &SelectionPolicy::LargestAllocation), Some(vec![0, 1]));
+}
+
+#[test]
+fn policy_values_must_match_the_processes() {
+    let state = any_order_state();
+    assert_eq!(state.check_safety_with(&SelectionPolicy::Priority(vec![1, 2])).unwrap_err(),
+               ShapeError { context: "the values of the 'priority' policy".to_string(), expected: 3, found: 2 });
+    assert_eq!(state.trace_safety_with(&SelectionPolicy::Deadline(vec![1, 2, 3, 4])).unwrap_err().to_string(),
+               "the values of the 'deadline' policy: expected 3 entries, found 4");
+    assert!(Repl::new(state, TableStyle::default()).with_policy(SelectionPolicy::Priority(vec![])).is_err());
+}
+
+#[test]
+fn trace_marks_only_the_chosen_process() {
+    let (_, steps) = any_order_state().trace_safety_with(&SelectionPolicy::Deadline(vec![5, 5, 1])).unwrap();
+    let first_pass: Vec<(usize, bool, bool)> = steps.iter()
+        .filter(|step| step.pass == 1)
+        .map(|step| (step.process_id, step.can_finish, step.new_work.is_some()))
+        .collect();
+    assert_eq!(first_pass, vec![(0, true, false), (1, true, false), (2, true, true)]);
+}
@@ -0,0 +1,62 @@
+//*********************************************************
+//
+// Release Tests
+//
+// Checks the release API: a valid release returns the
+// resources to the Available vector and adds them back to
+// the process's need, and an invalid one is refused
+// without changing the state.
+//
+//*********************************************************
+use banker::{BankerState, ReleaseError};
+
+//two resource types; P0 holds 2 of A and 1 of B, P1 holds 1 of each
+fn state() -> BankerState {
+    BankerState::new(vec![5, 3], vec![2, 1], vec![vec![3, 2], vec![2, 2]], vec![vec![2, 1], vec![1, 1]])
+}
+
+#[test]
+fn release_returns_the_resources() {
+    let mut state = state();
+    assert_eq!(state.release(0, &[2, 0]), Ok(()));
+    assert_eq!(state.available_vec(), &[4, 1]);
+    assert_eq!(state.allocation_matrix(), &[vec![0, 1], vec![1, 1]]);
+    assert_eq!(state.need_matrix(), &[vec![3, 1], vec![1, 1]]);
+
+    //releasing nothing is allowed, and changes nothing
+    let before = state.clone();
+    assert_eq!(state.release(1, &[0, 0]), Ok(()));
+    assert_eq!(state, before);
+}
+
+#[test]
+fn releasing_more_than_is_held_is_refused() {
+    let mut state = state();
+    let before = state.clone();
+    let error = state.release(1, &[1, 2]).unwrap_err();
+    assert_eq!(error, ReleaseError::ExceedsAllocation { resource: 1, released: 2, held: 1 });
+    assert_eq!(error.to_string(), "cannot release 2 of resource B, the process only holds 1");
+    assert_eq!(state, before);
+}
+
+#[test]
+fn negative_releases_are_refused() {
+    let mut state = state();
+    let before = state.clone();
+    assert_eq!(state.release(0, &[1, -1]), Err(ReleaseError::Negative { resource: 1, released: -1 }));
+    assert_eq!(state, before);
+}
+
+#[test]
+fn malformed_releases_are_refused() {
+    let mut state = state();
+    let before = state.clone();
+    assert_eq!(state.release(0, &[1]), Err(ReleaseError::WrongLength { expected: 2, found: 1 }));
+    assert_eq!(state.release(0, &[1, 0, 0]), Err(ReleaseError::WrongLength { expected: 2, found: 3 }));
+    assert_eq!(state, before);
+
+    let error = state.release(2, &[1, 0]).unwrap_err();
+    assert_eq!(error, ReleaseError::UnknownProcess { process_id: 2 });
+    assert_eq!(error.to_string(), "process 2 does not exist");
+    assert_eq!(state, before);
+}
@@ -0,0 +1,109 @@
+//*********************************************************
+//
+// Interactive Session Tests
+//
+// Drives a session through 'Repl::execute', checking what
+// each command prints and how it changes the state: that
+// granted requests can be undone while denied ones are
+// never recorded, that mistakes are reported without
+// changing anything, and that 'save' writes a file that
+// reads back as the same state.
+//
+//*********************************************************
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::process;
+
+use banker::display::TableStyle;
+use banker::parse::{read_input, LineReader};
+use banker::repl::Repl;
+
+//two named processes sharing 4 instances of one resource type, 2 of them available;
+//'a' needs 3 more and 'b' 1 more, so the state is safe if 'b' finishes first
+const NAMED: &str = "2 1\nprocesses: a b\n\n4\n\n2\n\n4\n2\n\n1\n1\n";
+
+//a session on the state in NAMED
+fn session() -> Repl {
+    let state = read_input(&mut LineReader::new(NAMED.as_bytes(), "test")).unwrap().into_state();
+    Repl::new(state, TableStyle::default())
+}
+
+//runs one command line, returning what it printed and whether the session goes on
+fn execute(repl: &mut Repl, line: &str) -> (String, bool) {
+    let mut out = vec![];
+    let more = repl.execute(line, &mut out).unwrap();
+    (String::from_utf8(out).unwrap(), more)
+}
+
+#[test]
+fn granted_requests_can_be_undone() {
+    let mut repl = session();
+    let initial = repl.state().clone();
+
+    assert_eq!(execute(&mut repl, "request b 1"), ("THE REQUEST CAN BE GRANTED.\n".to_string(), true));
+    assert_eq!(repl.state().available_vec(), &[1]);
+    assert_eq!(repl.state().allocation_matrix()[1], vec![2]);
+    assert_eq!(execute(&mut repl, "release b 2").0, "THE RELEASE CAN BE GRANTED.\n");
+
+    assert_eq!(execute(&mut repl, "undo").0, "Undid 'release b 2'.\n");
+    assert_eq!(execute(&mut repl, "undo").0, "Undid 'request b 1'.\n");
+    assert_eq!(repl.state(), &initial);
+    assert_eq!(execute(&mut repl, "undo").0, "error: there is nothing to undo\n");
+}
+
+#[test]
+fn denied_requests_are_not_recorded() {
+    let mut repl = session();
+    let initial = repl.state().clone();
+
+    let (out, _) = execute(&mut repl, "request a 2");
+    assert_eq!(out, "THE REQUEST CANNOT BE GRANTED.\n\
+                     Reason: granting the request would leave the system unsafe, blocking a, b\n");
+    assert_eq!(repl.state(), &initial);
+    assert_eq!(execute(&mut repl, "undo").0, "error: there is nothing to undo\n");
+
+    //once a request is granted, 'undo' skips the denied one after it
+    execute(&mut repl, "request b 1");
+    assert!(execute(&mut repl, "request a 2").0.starts_with("THE REQUEST CANNOT BE GRANTED.\n"));
+    assert_eq!(execute(&mut repl, "undo").0, "Undid 'request b 1'.\n");
+    assert_eq!(repl.state(), &initial);
+}
+
+#[test]
+fn need_of_a_named_process() {
+    let mut repl = session();
+    assert_eq!(execute(&mut repl, "need b").0, "The Need Vector is: \n   A \nb: 1 \n\n");
+    assert_eq!(execute(&mut repl, "need 0").0, "The Need Vector is: \n   A \na: 3 \n\n");
+}
+
+#[test]
+fn mistakes_are_reported_without_changing_the_state() {
+    let mut repl = session();
+    let initial = repl.state().clone();
+
+    assert_eq!(execute(&mut repl, "frobnicate").0, "error: unknown command 'frobnicate' (type 'help' for the commands)\n");
+    assert_eq!(execute(&mut repl, "need c").0, "error: 'c' is not a process name or id\n");
+    assert_eq!(execute(&mut repl, "request 7 1").0, "error: '7' is not a process name or id\n");
+    assert_eq!(execute(&mut repl, "request a 1 1").0, "error: expected 1 values, one per resource type, found 2\n");
+    assert_eq!(execute(&mut repl, "request a x").0, "error: 'x' is not an integer\n");
+    assert_eq!(execute(&mut repl, "undo now").0, "error: wrong arguments for 'undo' (type 'help' for the commands)\n");
+    assert_eq!(repl.state(), &initial);
+
+    //blank lines are ignored, and 'quit' ends the session
+    assert_eq!(execute(&mut repl, "   "), (String::new(), true));
+    assert_eq!(execute(&mut repl, "quit"), (String::new(), false));
+}
+
+#[test]
+fn saved_state_reads_back() {
+    let mut repl = session();
+    execute(&mut repl, "request b 1");
+
+    let path = env::temp_dir().join(format!("banker-repl-test-{}.txt", process::id()));
+    let (out, _) = execute(&mut repl, &format!("save {}", path.display()));
+    assert_eq!(out, format!("Saved the state to '{}'.\n", path.display()));
+    let saved = read_input(&mut LineReader::new(BufReader::new(File::open(&path).unwrap()), "saved")).map(|input| input.into_state());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(saved.as_ref(), Ok(repl.state()));
+}
@@ -0,0 +1,86 @@
+//*********************************************************
+//
+// Request Tests
+//
+// Checks the transactional request API: a request that is
+// denied, aborted or dropped leaves the state exactly as
+// it was, and a committed one keeps its allocation.
+//
+//*********************************************************
+use banker::{BankerState, RequestOutcome};
+
+//one resource type with 3 instances, one of them available; P0 needs 1 more
+//to finish and P1 needs 2, so the state is safe, but only while P0 can go first
+fn state() -> BankerState {
+    BankerState::new(vec![3], vec![1], vec![vec![2], vec![3]], vec![vec![1], vec![1]])
+}
+
+#[test]
+fn denied_requests_leave_the_state_unchanged() {
+    let mut state = state();
+    let before = state.clone();
+
+    assert_eq!(state.request(1, &[1]), RequestOutcome::WouldBeUnsafe { blocked_processes: vec![0, 1] });
+    assert_eq!(state, before);
+    assert_eq!(state.request(0, &[2]), RequestOutcome::ExceedsClaim { resource: 0, requested: 2, need: 1 });
+    assert_eq!(state, before);
+    assert_eq!(state.request(1, &[2]), RequestOutcome::MustWait { resource: 0, requested: 2, available: 1 });
+    assert_eq!(state, before);
+    assert_eq!(state.request(2, &[1]), RequestOutcome::UnknownProcess { process_id: 2 });
+    assert_eq!(state, before);
+}
+
+#[test]
+fn aborted_request_is_undone() {
+    let mut state = state();
+    let before = state.clone();
+
+    let pending = state.begin_request(1, &[1]).unwrap();
+    assert_eq!(pending.state().available_vec(), &[0]);
+    assert_eq!(pending.state().allocation_matrix()[1], vec![2]);
+    assert_eq!(pending.state().need_matrix()[1], vec![1]);
+    assert!(!pending.state().is_safe_state());
+    pending.abort();
+    assert_eq!(state, before);
+}
+
+#[test]
+fn dropped_request_is_undone() {
+    let mut state = state();
+    let before = state.clone();
+
+    {
+        let pending = state.begin_request(0, &[1]).unwrap();
+        assert_eq!(pending.state().allocation_matrix()[0], vec![2]);
+    }
+    assert_eq!(state, before);
+}
+
+#[test]
+fn committed_request_keeps_its_allocation() {
+    let mut state = state();
+    state.begin_request(0, &[1]).unwrap().commit();
+    assert_eq!(state.available_vec(), &[0]);
+    assert_eq!(state.allocation_matrix(), &[vec![2], vec![1]]);
+    assert_eq!(state.need_matrix(), &[vec![0], vec![2]]);
+
+    //'request' grants the same request the same way
+    let mut granted = self::state();
+    assert_eq!(granted.request(0, &[1]), RequestOutcome::Granted);
+    assert_eq!(granted, state);
+}
+
+#[test]
+fn malformed_requests_are_rejected_before_any_change() {
+    let mut state = state();
+    let before = state.clone();
+
+    assert_eq!(state.request(1, &[-1]), RequestOutcome::Negative { resource: 0, requested: -1 });
+    assert_eq!(state, before);
+    assert_eq!(state.request(0, &[1, 0]), RequestOutcome::WrongLength { expected: 1, found: 2 });
+    assert_eq!(state, before);
+    assert_eq!(state.request(0, &[]), RequestOutcome::WrongLength { expected: 1, found: 0 });
+    assert_eq!(state, before);
+    assert_eq!(state.begin_request(0, &[-1]).unwrap_err(), RequestOutcome::Negative { resource: 0, requested: -1 });
+    assert_eq!(state, before);
+}
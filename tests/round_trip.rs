@@ -0,0 +1,128 @@
+//*********************************************************
+//
+// Round Trip Tests
+//
+// Checks that writing a state with 'serialize' and reading
+// the text back with 'parse' gives the same state and
+// events: parse -> write -> parse is lossless, and that a
+// state the text format cannot hold is refused instead.
+//
+//*********************************************************
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use banker::parse::{read_input, read_json_input, EventRow, LineReader, ParsedInput};
+use banker::serialize::to_text;
+use banker::validate::check_dimensions;
+use banker::{BankerState, Event, EventKind, Names};
+
+//the state and events of parsed input, which must match its declared dimensions
+fn into_scenario(input: ParsedInput) -> (BankerState, Vec<Event>) {
+    assert_eq!(check_dimensions(&input), vec![]);
+    let events = input.events.iter().map(EventRow::to_event).collect();
+    (input.into_state(), events)
+}
+
+//reads a scenario from text in the text format
+fn parse_text(text: &str) -> (BankerState, Vec<Event>) {
+    into_scenario(read_input(&mut LineReader::new(text.as_bytes(), "test")).unwrap())
+}
+
+//writes the scenario in 'text' and checks that reading it back gives the same scenario
+fn assert_round_trip(text: &str) {
+    let (state, events) = parse_text(text);
+    let written = to_text(&state, &events).unwrap();
+    assert_eq!(parse_text(&written), (state.clone(), events.clone()), "written text:\n{}", written);
+
+    //writing is stable once the text has been normalized
+    assert_eq!(to_text(&state, &events).unwrap(), written);
+}
+
+fn test_file(name: &str) -> String {
+    fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testfiles").join(name)).unwrap()
+}
+
+#[test]
+fn bundled_examples_round_trip() {
+    for name in ["example1", "example2", "example3", "example6", "example7", "example8", "example9", "example11"] {
+        assert_round_trip(&test_file(name));
+    }
+}
+
+#[test]
+fn named_scenario_with_releases_round_trips() {
+    assert_round_trip("\
+4 3
+resources: gpu lic db
+processes: etl web api batch
+
+10 5 6
+
+2 3 2
+
+3 3 2
+6 3 3
+3 3 4
+4 3 2
+
+1 0 0
+5 1 1
+2 1 1
+0 0 2
+
+etl: 1 0 1
+request api: 1 0 0
+release web: 1 0 0
+release 3: 0 0 2
+");
+}
+
+#[test]
+fn messy_file_is_normalized() {
+    let messy = "\u{feff}# generated\r\n4   3\r\n9\t3  6   # totals\r\n\r\n\r\n1 1 2\r\n\
+                 3 2 2\r\n6 1 3\r\n3 1 4\r\n4 2 2\r\n1 0 0\r\n5 1 1\r\n2 1 1\r\n0 0 2\r\nrequest\t0 :  1 0 1\r\n";
+    let (state, events) = parse_text(messy);
+    assert_eq!(to_text(&state, &events).unwrap(), "4 3\n\n9 3 6\n\n1 1 2\n\n3 2 2\n6 1 3\n3 1 4\n4 2 2\n\n1 0 0\n5 1 1\n2 1 1\n0 0 2\n\n0:1 0 1\n");
+    assert_round_trip(messy);
+}
+
+#[test]
+fn granted_state_round_trips() {
+    let (mut state, events) = parse_text(&test_file("example3"));
+    for event in &events {
+        assert!(state.apply_event(event).is_granted());
+    }
+    let (read_back, no_events) = parse_text(&to_text(&state, &[]).unwrap());
+    assert_eq!(read_back, state);
+    assert!(no_events.is_empty());
+}
+
+#[test]
+fn json_input_round_trips_through_text() {
+    let input = read_json_input(&test_file("example10.json"), "example10.json").unwrap();
+    let (state, events) = into_scenario(input);
+    assert_eq!(parse_text(&to_text(&state, &events).unwrap()), (state, events));
+}
+
+#[test]
+fn events_past_the_named_processes_are_written_by_id() {
+    let names = Names { resources: None, processes: Some(vec!["a".to_string(), "b".to_string()]) };
+    let state = BankerState::new(vec![4], vec![2], vec![vec![2], vec![2]], vec![vec![1], vec![1]]).with_names(names).unwrap();
+    let events = vec![
+        Event { kind: EventKind::Request, process_id: 1, vector: vec![1] },
+        Event { kind: EventKind::Release, process_id: 7, vector: vec![1] },
+    ];
+    assert!(to_text(&state, &events).unwrap().ends_with("\n\nb:1\nrelease P7:1\n"));
+}
+
+#[test]
+fn states_without_resource_types_are_refused() {
+    let state = BankerState::new(vec![], vec![], vec![vec![]; 2], vec![vec![]; 2]);
+    assert_eq!(to_text(&state, &[]).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+    //nor can the text format declare one
+    let error = read_input(&mut LineReader::new("2 0\n\n\n".as_bytes(), "test")).unwrap_err();
+    assert_eq!(error.message(), "expected at least one resource type, found '0'");
+    assert_eq!(error.position().map(|position| (position.line, position.column)), Some((1, 3)));
+}
@@ -0,0 +1,66 @@
+//*********************************************************
+//
+// Safe Sequence Tests
+//
+// Checks that counting the safe sequences with the subset
+// dynamic program agrees with listing them by
+// backtracking, and that every listed sequence is safe.
+//
+//*********************************************************
+use banker::generate::generate_state;
+use banker::BankerState;
+
+//whether the processes of a state can finish in the order 'sequence'
+fn is_safe_sequence(state: &BankerState, sequence: &[usize]) -> bool {
+    let mut work_vec = state.available_vec().to_vec();
+    for process in sequence {
+        if state.need_matrix()[*process].iter().zip(&work_vec).any(|(need, work)| need > work) {
+            return false;
+        }
+        for (work, allocated) in work_vec.iter_mut().zip(&state.allocation_matrix()[*process]) {
+            *work += allocated;
+        }
+    }
+    sequence.len() == state.num_processes()
+}
+
+//a state of 'num_processes' processes that have all reached their maximum, so any order is safe
+fn satisfied_state(num_processes: usize) -> BankerState {
+    let allocation = vec![vec![1, 1]; num_processes];
+    BankerState::new(vec![num_processes as i32 + 1, num_processes as i32], vec![1, 0], allocation.clone(), allocation)
+}
+
+#[test]
+fn count_matches_enumeration() {
+    for seed in 0..40 {
+        let state = generate_state(6, 3, seed);
+        let (sequences, complete) = state.safe_sequences(usize::MAX);
+        assert!(complete);
+        assert!(!sequences.is_empty(), "generated states are safe");
+        assert!(sequences.iter().all(|sequence| is_safe_sequence(&state, sequence)));
+        assert_eq!(state.count_safe_sequences(), Some(sequences.len() as u128), "seed {}", seed);
+    }
+}
+
+#[test]
+fn enumeration_stops_at_the_limit() {
+    let state = satisfied_state(4);
+    let (sequences, complete) = state.safe_sequences(5);
+    assert_eq!(sequences, vec![vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3], vec![0, 2, 3, 1], vec![0, 3, 1, 2]]);
+    assert!(!complete);
+    assert_eq!(state.safe_sequences(24), (state.safe_sequences(usize::MAX).0, true));
+    assert_eq!(state.count_safe_sequences(), Some(24));
+}
+
+#[test]
+fn unsafe_state_has_no_safe_sequences() {
+    let state = BankerState::new(vec![3, 2], vec![1, 0], vec![vec![2, 2], vec![2, 2]], vec![vec![1, 1], vec![1, 1]]);
+    assert_eq!(state.safe_sequences(10), (vec![], true));
+    assert_eq!(state.count_safe_sequences(), Some(0));
+}
+
+#[test]
+fn counts_many_processes() {
+    assert_eq!(satisfied_state(12).count_safe_sequences(), Some(479_001_600));
+    assert_eq!(satisfied_state(26).count_safe_sequences(), None);
+}
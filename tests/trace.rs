@@ -0,0 +1,70 @@
+//*********************************************************
+//
+// Safety Trace Tests
+//
+// Checks the steps the safety algorithm records on the
+// textbook example in src/testfiles/example6: the Work
+// vector before and after each step, the process chosen
+// to finish, and where each pass over the processes
+// begins.
+//
+//*********************************************************
+use std::fs;
+use std::path::Path;
+
+use banker::parse::{read_input, LineReader};
+use banker::{BankerState, Safety, TraceStep};
+
+//the state of src/testfiles/example6
+fn example6() -> BankerState {
+    let text = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testfiles/example6")).unwrap();
+    read_input(&mut LineReader::new(text.as_bytes(), "example6")).unwrap().into_state()
+}
+
+//a step of the trace; 'new_work' is empty for a process that did not finish
+fn step(pass: usize, process_id: usize, need: [i32; 3], work: [i32; 3], can_finish: bool, new_work: &[i32]) -> TraceStep {
+    TraceStep {
+        pass,
+        process_id,
+        need: need.to_vec(),
+        work: work.to_vec(),
+        can_finish,
+        new_work: (!new_work.is_empty()).then(|| new_work.to_vec()),
+    }
+}
+
+#[test]
+fn textbook_example_trace() {
+    let (safety, steps) = example6().trace_safety();
+    assert_eq!(safety, Safety::Safe(vec![1, 3, 0, 2, 4]));
+    assert_eq!(steps, vec![
+        step(1, 0, [7, 4, 3], [3, 3, 2], false, &[]),
+        step(1, 1, [1, 2, 2], [3, 3, 2], true, &[5, 3, 2]),
+        step(2, 0, [7, 4, 3], [5, 3, 2], false, &[]),
+        step(2, 2, [6, 0, 0], [5, 3, 2], false, &[]),
+        step(2, 3, [0, 1, 1], [5, 3, 2], true, &[7, 4, 3]),
+        step(3, 0, [7, 4, 3], [7, 4, 3], true, &[7, 5, 3]),
+        step(4, 2, [6, 0, 0], [7, 5, 3], true, &[10, 5, 5]),
+        step(5, 4, [4, 3, 1], [10, 5, 5], true, &[10, 5, 7]),
+    ]);
+}
+
+#[test]
+fn each_pass_starts_from_the_work_the_last_one_left() {
+    let (_, steps) = example6().trace_safety();
+
+    //the steps of a pass compare against the same Work vector, and the chosen process ends the pass
+    for pair in steps.windows(2) {
+        match &pair[0].new_work {
+            Some(new_work) => {
+                assert_eq!(pair[1].pass, pair[0].pass + 1);
+                assert_eq!(&pair[1].work, new_work);
+            }
+            None => {
+                assert_eq!(pair[1].pass, pair[0].pass);
+                assert_eq!(pair[1].work, pair[0].work);
+            }
+        }
+    }
+    assert_eq!(steps.last().unwrap().new_work, Some(vec![10, 5, 7]));
+}
@@ -0,0 +1,68 @@
+//*********************************************************
+//
+// Unsafe Report Tests
+//
+// Checks how an unsafe state is explained: which processes
+// could finish before the safety algorithm got stuck, the
+// Work vector it got stuck with, how far each blocked
+// process is short, and the bottleneck resources.
+//
+//*********************************************************
+use banker::display::{print_unsafe_report, TableStyle};
+use banker::{BankerState, Names, ResourceDeficit, Safety, UnsafeReport};
+
+//P0 can finish, leaving Work at [2, 1]; then P1 is short 1 of B and P2 is short 2 of A
+fn stuck_state() -> BankerState {
+    BankerState::new(vec![5, 3], vec![1, 0], vec![vec![2, 1], vec![4, 3], vec![5, 2]], vec![vec![1, 1], vec![2, 1], vec![1, 1]])
+}
+
+//the report of an unsafe state
+fn report(state: &BankerState) -> UnsafeReport {
+    match state.check_safety() {
+        Safety::Unsafe(report) => report,
+        Safety::Safe(sequence) => panic!("expected an unsafe state, found the safe sequence {:?}", sequence),
+    }
+}
+
+#[test]
+fn report_names_the_blocked_processes_and_their_deficits() {
+    let report = report(&stuck_state());
+    assert_eq!(report, UnsafeReport {
+        finished: vec![0],
+        blocked: vec![1, 2],
+        work: vec![2, 1],
+        deficits: vec![vec![0, 1], vec![2, 0]],
+    });
+    assert_eq!(report.resource_deficits(), vec![
+        ResourceDeficit { resource: 0, processes: vec![2], largest: 2, total: 2 },
+        ResourceDeficit { resource: 1, processes: vec![1], largest: 1, total: 1 },
+    ]);
+}
+
+#[test]
+fn report_is_printed_by_name() {
+    let names = Names { resources: Some(vec!["cpu".to_string(), "gpu".to_string()]), processes: None };
+    let state = stuck_state().with_names(names).unwrap();
+    let mut out = vec![];
+    print_unsafe_report(&mut out, &report(&state), state.names(), TableStyle::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("Finished before the system got stuck: P0\n\n"), "{}", out);
+    assert!(out.ends_with("\
+Blocked processes:
+  P1: needs 1 more of gpu
+  P2: needs 2 more of cpu
+Bottleneck resources:
+  cpu: short by 2 in total (2 at most), blocking P2
+  gpu: short by 1 in total (1 at most), blocking P1
+
+"), "{}", out);
+}
+
+#[test]
+fn deficits_do_not_overflow() {
+    //a negative Available vector is only accepted in lenient mode
+    let state = BankerState::new(vec![i32::MAX], vec![-5], vec![vec![i32::MAX]], vec![vec![0]]);
+    let report = report(&state);
+    assert_eq!(report.deficits, vec![vec![i32::MAX as i64 + 5]]);
+    assert_eq!(report.resource_deficits()[0].total, i32::MAX as i64 + 5);
+}